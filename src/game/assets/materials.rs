@@ -1,5 +1,7 @@
+use crate::game::assets::AssetType;
 use crate::game::assets::asset_ref;
 use crate::game::assets::asset_ref::AssetRefError;
+use crate::game::levels::surface::{MATERIAL_SURFACE_LABEL, Surface, SurfacePreset};
 use bevy::asset::io::Reader;
 use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
 use bevy::prelude::*;
@@ -7,6 +9,9 @@ use bevy::render::render_resource::Face;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Extension of material files, without the leading dot.
+pub const MATERIAL_EXTENSION: &str = "material.json";
+
 #[derive(Debug, Default)]
 pub struct MaterialLoader;
 
@@ -26,11 +31,14 @@ impl AssetLoader for MaterialLoader {
 
         let json: StandardMaterialJson = serde_json::from_str(&str)?;
 
+        let surface = json.bind_surface(load_context)?;
+        load_context.add_labeled_asset(MATERIAL_SURFACE_LABEL.to_string(), surface);
+
         json.bind(load_context)
     }
 
     fn extensions(&self) -> &[&str] {
-        &["material.json"]
+        &[MATERIAL_EXTENSION]
     }
 }
 
@@ -42,6 +50,10 @@ pub enum MaterialLoadError {
     Io(#[from] std::io::Error),
     #[error("Json parse error {0}")]
     Json(#[from] serde_json::Error),
+    #[error("Friction is {0} but should be greater than zero")]
+    Friction(f32),
+    #[error("Bounce is {0} but should be between 0 and 1")]
+    Bounce(f32),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -72,6 +84,18 @@ struct StandardMaterialJson {
 
     #[serde(default)]
     pub alpha_mode: AlphaModeJson,
+
+    #[serde(default)]
+    pub surface: Option<SurfacePreset>,
+
+    #[serde(default)]
+    pub friction: Option<f32>,
+
+    #[serde(default)]
+    pub bounce: Option<f32>,
+
+    #[serde(default)]
+    pub rolling_sound: Option<String>,
 }
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -132,6 +156,10 @@ impl Default for StandardMaterialJson {
             metallic_roughness_texture: None,
             cull_mode: Default::default(),
             alpha_mode: Default::default(),
+            surface: None,
+            friction: None,
+            bounce: None,
+            rolling_sound: None,
         }
     }
 }
@@ -157,6 +185,25 @@ impl StandardMaterialJson {
             ..default()
         })
     }
+
+    pub fn bind_surface(
+        &self,
+        load_context: &mut LoadContext,
+    ) -> Result<Surface, MaterialLoadError> {
+        if let Some(friction) = self.friction.filter(|friction| *friction <= 0.0) {
+            return Err(MaterialLoadError::Friction(friction));
+        }
+        if let Some(bounce) = self.bounce.filter(|bounce| !(0.0..=1.0).contains(bounce)) {
+            return Err(MaterialLoadError::Bounce(bounce));
+        }
+
+        Ok(Surface {
+            preset: self.surface,
+            friction: self.friction,
+            bounce: self.bounce,
+            rolling_sound: bind_handle(&self.rolling_sound, load_context)?,
+        })
+    }
 }
 
 fn default_base_color() -> Color {
@@ -175,10 +222,10 @@ fn default_metallic() -> f32 {
     0.0
 }
 
-fn bind_handle(
+fn bind_handle<A: Asset + AssetType>(
     to_bind: &Option<String>,
     load_context: &mut LoadContext,
-) -> Result<Option<Handle<Image>>, MaterialLoadError> {
+) -> Result<Option<Handle<A>>, MaterialLoadError> {
     Ok(to_bind
         .as_ref()
         .map(|tex| asset_ref::load(tex, load_context))
@@ -209,4 +256,24 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn test_deserialize_surface() {
+        let json = json!({
+            "surface": "ice",
+            "bounce": 0.5
+        });
+
+        let deserialized: StandardMaterialJson =
+            serde_json::from_value(json).expect("error deserializing");
+
+        assert_eq!(
+            deserialized,
+            StandardMaterialJson {
+                surface: Some(SurfacePreset::Ice),
+                bounce: Some(0.5),
+                ..default()
+            }
+        )
+    }
 }
@@ -8,6 +8,7 @@ use crate::game::assets::fonts::{FontNames, LoadedFonts, insert_fonts};
 use crate::game::assets::materials::MaterialLoader;
 use crate::game::assets::preload::{Preloads, PreloadsLoader, load_preloads, load_preloads_system};
 use crate::game::levels::index::load_level_index;
+use crate::game::levels::surface::Surface;
 use bevy::app::MainScheduleOrder;
 use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
@@ -28,6 +29,7 @@ impl Plugin for BuiltinAssetsPlugin {
             .insert_after(PreUpdate, AssetProcess);
 
         app.init_asset::<Preloads>()
+            .init_asset::<Surface>()
             .init_asset_loader::<PreloadsLoader>()
             .init_asset_loader::<MaterialLoader>()
             .init_resource::<BuiltinAssetsState>()
@@ -1,8 +1,11 @@
 use crate::game::game_state::GameState;
 use crate::game::input::PlayerInput;
+use crate::game::levels::checkpoint::ActiveCheckpoint;
+use crate::game::levels::gravity::{GravityUp, up_frame};
 use crate::game::levels::{LevelReadyEvent, LevelRestartEvent, PlayerSpawnPoint};
 use crate::game::logic::{Player, spawn_transform};
 use crate::game::state::AppState;
+use avian3d::prelude::*;
 use bevy::core_pipeline::tonemapping::Tonemapping;
 use bevy::post_process::bloom::{Bloom, BloomCompositeMode};
 use bevy::prelude::*;
@@ -26,6 +29,7 @@ impl Plugin for CameraPlugin {
 
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Component, Reflect)]
 #[reflect(Debug, Default, Clone, PartialEq, Component)]
+#[require(CameraUp)]
 pub struct PlayerCamera {
     pub pitch: f32,
     pub yaw: f32,
@@ -42,10 +46,26 @@ impl Default for PlayerCamera {
     }
 }
 
+/// The up direction the camera orbits around, which eases towards the player's gravity.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+pub struct CameraUp(pub Dir3);
+
+impl Default for CameraUp {
+    fn default() -> Self {
+        Self(Dir3::Y)
+    }
+}
+
 impl PlayerCamera {
     pub fn get_looking(&self) -> Vec3 {
         -Vec3::new(self.yaw.sin(), 0.0, self.yaw.cos())
     }
+
+    pub fn rotate(&mut self, delta: Vec2) {
+        self.yaw += -delta.x;
+        self.pitch = (self.pitch - delta.y).clamp(-PI / 2.0 + 0.001, PI / 2.0 - 0.001);
+    }
 }
 
 fn setup_camera(mut cmd: Commands) {
@@ -69,8 +89,7 @@ fn rotate_camera(
 ) {
     for mouse in mouse.read() {
         if let PlayerInput::CameraMovement(delta) = mouse {
-            camera.yaw += -delta.x;
-            camera.pitch = (camera.pitch - delta.y).clamp(-PI / 2.0 + 0.001, PI / 2.0 - 0.001);
+            camera.rotate(*delta);
         }
     }
 }
@@ -85,29 +104,45 @@ fn zoom_camera(mut camera: Single<&mut PlayerCamera>, mut mouse: MessageReader<P
 
 fn on_start_level(
     _on: On<LevelReadyEvent>,
-    mut camera: Single<&mut PlayerCamera>,
+    camera: Single<(&mut PlayerCamera, &mut CameraUp)>,
     spawn_point: Query<&Transform, (With<PlayerSpawnPoint>, Without<Player>)>,
+    checkpoint: Res<ActiveCheckpoint>,
+    gravity: Res<Gravity>,
 ) {
-    **camera = apply_spawn_point_rotation(spawn_point);
+    apply_spawn_point_rotation(camera, spawn_point, &checkpoint, &gravity);
 }
 
 fn on_restart_level(
     _on: On<LevelRestartEvent>,
-    mut camera: Single<&mut PlayerCamera>,
+    camera: Single<(&mut PlayerCamera, &mut CameraUp)>,
     spawn_point: Query<&Transform, (With<PlayerSpawnPoint>, Without<Player>)>,
+    checkpoint: Res<ActiveCheckpoint>,
+    gravity: Res<Gravity>,
 ) {
-    **camera = apply_spawn_point_rotation(spawn_point);
+    apply_spawn_point_rotation(camera, spawn_point, &checkpoint, &gravity);
 }
 
+// the player spawns with the up of the level's gravity, so the camera starts out orbiting it
 fn apply_spawn_point_rotation(
+    mut camera: Single<(&mut PlayerCamera, &mut CameraUp)>,
     spawn_point: Query<&Transform, (With<PlayerSpawnPoint>, Without<Player>)>,
-) -> PlayerCamera {
-    let spawn_transform = spawn_transform(spawn_point);
-    let (axis, angle) = spawn_transform.rotation.to_axis_angle();
-    let yaw = angle * axis.dot(Vec3::Y);
-    PlayerCamera { yaw, ..default() }
+    checkpoint: &ActiveCheckpoint,
+    gravity: &Gravity,
+) {
+    let (ref mut player_camera, ref mut camera_up) = *camera;
+    let up = GravityUp::from_gravity(gravity.0).unwrap_or_default().0;
+
+    let spawn_transform = spawn_transform(spawn_point, checkpoint);
+    let forward = up_frame(up).inverse() * (spawn_transform.rotation * Vec3::NEG_Z);
+    // the camera looks along -(sin yaw, 0, cos yaw) in the up frame
+    let yaw = f32::atan2(-forward.x, -forward.z);
+
+    **player_camera = PlayerCamera { yaw, ..default() };
+    camera_up.0 = up;
 }
 
-fn reset_camera(mut camera: Single<&mut PlayerCamera>) {
-    **camera = PlayerCamera::default();
+fn reset_camera(mut camera: Single<(&mut PlayerCamera, &mut CameraUp)>) {
+    let (ref mut player_camera, ref mut camera_up) = *camera;
+    **player_camera = PlayerCamera::default();
+    **camera_up = CameraUp::default();
 }
@@ -0,0 +1,295 @@
+//! `--check` mode, which loads every level in the index and reports problems without starting the
+//! game.
+//!
+//! Levels that fail to bind are errors and make the check fail. Problems that only show up while
+//! playing, like buttons that don't open anything or missing death planes, are reported as
+//! warnings.
+
+use crate::game::assets::asset_types;
+use crate::game::assets::materials::{MATERIAL_EXTENSION, MaterialLoader};
+use crate::game::assets::preload::{
+    PRELOAD_INDEX_PATH, PRELOAD_PARTIALS, PRELOAD_PREFIX, Preloads, PreloadsLoader,
+};
+use crate::game::levels::index::{LEVEL_INDEX_PATH, LevelIndex, LevelIndexLoader};
+use crate::game::levels::serial::SerialLevelLoader;
+use crate::game::levels::serial::level::SerialLevel;
+use crate::game::levels::serial::level::plane::{SerialPlane, SerialPlaneType};
+use crate::game::levels::surface::Surface;
+use bevy::app::PluginsState;
+use bevy::asset::io::file::FileAssetReader;
+use bevy::asset::{AssetPath, LoadState, UntypedAssetId};
+use bevy::prelude::*;
+use kdl::KdlDocument;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const LOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub fn physball_check_main() -> AppExit {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+
+    // level dependencies are never loaded, but their handles still need a place to live
+    asset_types!(_Asset, app.init_asset::<_Asset>());
+    app.init_asset::<Preloads>()
+        .init_asset::<LevelIndex>()
+        .init_asset::<SerialLevel>()
+        .init_asset::<Surface>()
+        .init_asset_loader::<PreloadsLoader>()
+        .init_asset_loader::<MaterialLoader>()
+        .init_asset_loader::<LevelIndexLoader>()
+        .init_asset_loader::<SerialLevelLoader>();
+
+    while app.plugins_state() == PluginsState::Adding {
+        bevy::tasks::tick_global_task_pools_on_main_thread();
+    }
+    app.finish();
+    app.cleanup();
+
+    let asset_server = app.world().resource::<AssetServer>().clone();
+
+    // preloads need to be loaded before any levels so that `preload:` references resolve
+    let preloads = asset_server.load::<Preloads>(PRELOAD_INDEX_PATH);
+    let index = asset_server.load::<LevelIndex>(LEVEL_INDEX_PATH);
+    wait_for_loads(&mut app, &[preloads.id().untyped(), index.id().untyped()]);
+
+    for (what, id) in [
+        ("preloads", preloads.id().untyped()),
+        ("level index", index.id().untyped()),
+    ] {
+        if let Some(err) = load_error(&asset_server, id) {
+            eprintln!("error: failed to load {what}: {err}");
+            return AppExit::error();
+        }
+    }
+
+    let index = app
+        .world()
+        .resource::<Assets<LevelIndex>>()
+        .get(&index)
+        .unwrap()
+        .clone();
+
+    let levels = index
+        .order
+        .iter()
+        .map(|name| {
+            let level = &index.levels[name];
+            (level, asset_server.load::<SerialLevel>(&level.path))
+        })
+        .collect::<Vec<_>>();
+    wait_for_loads(
+        &mut app,
+        &levels
+            .iter()
+            .map(|(_, handle)| handle.id().untyped())
+            .collect::<Vec<_>>(),
+    );
+
+    let assets_root = FileAssetReader::get_base_path().join(AssetPlugin::default().file_path);
+    let level_assets = app.world().resource::<Assets<SerialLevel>>();
+    let mut used_materials = HashSet::new();
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    for (level, handle) in levels.iter() {
+        if let Some(err) = load_error(&asset_server, handle.id().untyped()) {
+            eprintln!("error: level '{}' failed to load: {}", &level.name, err);
+            errors += 1;
+            continue;
+        }
+
+        let mut level_warnings = lint_level(level_assets.get(handle).unwrap());
+
+        let path = AssetPath::from(level.path.as_str());
+        match std::fs::read_to_string(assets_root.join(path.path())) {
+            Ok(source) => lint_references(&path, &source, &mut used_materials, &mut level_warnings),
+            Err(err) => level_warnings.push(format!("unable to read level source: {err}")),
+        }
+
+        for warning in level_warnings.iter() {
+            eprintln!("warning: level '{}': {}", &level.name, warning);
+        }
+        warnings += level_warnings.len();
+    }
+
+    for material in unused_materials(&assets_root, &used_materials) {
+        eprintln!(
+            "warning: material '{}' is not used by any level",
+            material.display()
+        );
+        warnings += 1;
+    }
+
+    println!(
+        "Checked {} levels: {} errors, {} warnings",
+        levels.len(),
+        errors,
+        warnings
+    );
+
+    if errors > 0 {
+        AppExit::error()
+    } else {
+        AppExit::Success
+    }
+}
+
+fn wait_for_loads(app: &mut App, ids: &[UntypedAssetId]) {
+    let asset_server = app.world().resource::<AssetServer>().clone();
+    let start = Instant::now();
+
+    while start.elapsed() < LOAD_TIMEOUT
+        && ids.iter().any(|id| {
+            matches!(
+                asset_server.load_state(*id),
+                LoadState::NotLoaded | LoadState::Loading
+            )
+        })
+    {
+        app.update();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn load_error(asset_server: &AssetServer, id: UntypedAssetId) -> Option<String> {
+    match asset_server.load_state(id) {
+        LoadState::Loaded => None,
+        LoadState::Failed(err) => Some(err.to_string()),
+        _ => Some("timed out while loading".to_string()),
+    }
+}
+
+/// Checks a bound level for problems that would only show up while playing it.
+fn lint_level(level: &SerialLevel) -> Vec<String> {
+    let mut warnings = vec![];
+
+    let required_buttons = level
+        .button_doors
+        .iter()
+        .flat_map(|door| door.requires.buttons())
+        .map(|id| id.value.as_str())
+        .collect::<HashSet<_>>();
+    // buttons that drive a signal are used by whatever listens to it instead
+    for button in level.buttons.iter() {
+        if button.signal.is_none() && !required_buttons.contains(button.id.value.as_str()) {
+            warnings.push(format!(
+                "button '{}' doesn't open any door",
+                &button.id.value
+            ));
+        }
+    }
+
+    let death_planes = level
+        .planes
+        .iter()
+        .filter(|plane| matches!(plane.ty, SerialPlaneType::Death))
+        .collect::<Vec<_>>();
+    if death_planes.is_empty() && level.bounds.bounds.is_none() {
+        warnings
+            .push("no death plane or bounds, players who fall off will never respawn".to_string());
+    }
+
+    for (what, point) in [("spawn", level.spawn), ("finish", level.finish)] {
+        if death_planes
+            .iter()
+            .any(|plane| is_below(plane, point.translation))
+        {
+            warnings.push(format!(
+                "{what} is below a death plane and can't be reached"
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Whether the point is on the underside of a plane and within its extents. Tilted planes are
+/// measured along their normal, and nothing is below planes that don't face up at all.
+fn is_below(plane: &SerialPlane, point: Vec3) -> bool {
+    if (plane.trans.rotation * Vec3::Y).y <= 0.0 {
+        return false;
+    }
+
+    let local = plane
+        .trans
+        .compute_affine()
+        .inverse()
+        .transform_point3(point);
+    local.y <= 0.0 && local.x.abs() <= plane.width / 2.0 && local.z.abs() <= plane.length / 2.0
+}
+
+/// Checks the asset references in a level's source, and collects the materials it uses.
+fn lint_references(
+    level_path: &AssetPath,
+    source: &str,
+    used_materials: &mut HashSet<PathBuf>,
+    warnings: &mut Vec<String>,
+) {
+    // levels that fail to parse have already been reported
+    let Ok(doc) = source.parse::<KdlDocument>() else {
+        return;
+    };
+
+    let preloads = PRELOAD_PARTIALS.lock().unwrap();
+    for value in string_values(&doc) {
+        if let Some(preload) = value.strip_prefix(PRELOAD_PREFIX) {
+            if !preloads
+                .values()
+                .any(|of_type| of_type.contains_key(preload))
+            {
+                warnings.push(format!("unresolved preload reference '{value}'"));
+            }
+        } else if is_material(value)
+            && let Ok(path) = level_path.resolve_embed(value)
+        {
+            used_materials.insert(path.path().to_path_buf());
+        }
+    }
+}
+
+fn is_material(path: &str) -> bool {
+    path.strip_suffix(MATERIAL_EXTENSION)
+        .is_some_and(|stem| stem.ends_with('.'))
+}
+
+/// Every string value in a document, including the ones in child nodes.
+fn string_values(doc: &KdlDocument) -> Vec<&str> {
+    doc.nodes()
+        .iter()
+        .flat_map(|node| {
+            node.entries()
+                .iter()
+                .filter_map(|entry| entry.value().as_string())
+                .chain(node.children().map(string_values).unwrap_or_default())
+        })
+        .collect()
+}
+
+/// Material files in the levels directory that no level refers to.
+fn unused_materials(assets_root: &Path, used_materials: &HashSet<PathBuf>) -> Vec<PathBuf> {
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(&path, files);
+            } else if is_material(&path.to_string_lossy()) {
+                files.push(path);
+            }
+        }
+    }
+
+    let mut files = vec![];
+    walk(&assets_root.join("levels"), &mut files);
+    files.sort();
+
+    files
+        .into_iter()
+        .filter_map(|file| file.strip_prefix(assets_root).ok().map(Path::to_path_buf))
+        .filter(|file| !used_materials.contains(file))
+        .collect()
+}
@@ -1,4 +1,6 @@
 use crate::game::input::PlayerInput;
+use crate::game::levels::force::ForceFieldGizmos;
+use crate::game::levels::launcher::LauncherGizmos;
 use avian3d::prelude::PhysicsGizmos;
 use bevy::prelude::*;
 
@@ -27,6 +29,13 @@ fn toggle_gizmos(
         if let PlayerInput::ToggleGizmos = input {
             let (config, _) = config_store.config_mut::<PhysicsGizmos>();
             config.enabled ^= true;
+            let enabled = config.enabled;
+
+            let (config, _) = config_store.config_mut::<ForceFieldGizmos>();
+            config.enabled = enabled;
+
+            let (config, _) = config_store.config_mut::<LauncherGizmos>();
+            config.enabled = enabled;
         }
     }
 }
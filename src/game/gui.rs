@@ -80,6 +80,18 @@ pub fn title(fonts: &Preloads, text: impl ToString) -> impl Bundle {
     )
 }
 
+pub fn label(fonts: &Preloads, text: impl ToString) -> impl Bundle {
+    (
+        Text::new(text.to_string()),
+        TextFont {
+            font: fonts.text_font(),
+            font_size: 32.0,
+            ..default()
+        },
+        TextColor(TEXT_COLOR),
+    )
+}
+
 pub struct ButtonSettings {
     pub width: Val,
     pub min_width: Val,
@@ -1,5 +1,6 @@
 use super::PlayerInput;
 use crate::game::game_state::GameState;
+use crate::game::replay::ReplayPlayback;
 use crate::game::settings::GamePrefs;
 use bevy::input::keyboard::Key;
 use bevy::input::mouse::{MouseMotion, MouseWheel};
@@ -12,8 +13,7 @@ pub fn build(app: &mut App) {
         (
             pause_play,
             fn_key_input,
-            keyboard_input,
-            mouse_input,
+            (keyboard_input, mouse_input).run_if(not(resource_exists::<ReplayPlayback>)),
             mouse_scroll,
         ),
     );
@@ -61,6 +61,10 @@ pub fn keyboard_input(mut writer: MessageWriter<PlayerInput>, input: Res<ButtonI
     if input.pressed(KeyCode::Space) {
         writer.write(PlayerInput::Jump);
     }
+
+    if input.just_pressed(KeyCode::KeyE) {
+        writer.write(PlayerInput::Activate);
+    }
 }
 
 pub fn mouse_input(
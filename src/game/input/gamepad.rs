@@ -1,11 +1,18 @@
 use crate::game::input::PlayerInput;
+use crate::game::replay::ReplayPlayback;
 use crate::game::settings::GamePrefs;
 use bevy::prelude::*;
 
 // TODO: UI navigation
 
 pub fn build(app: &mut App) {
-    app.add_systems(PreUpdate, (joystick_input, gamepad_buttons));
+    app.add_systems(
+        PreUpdate,
+        (
+            gamepad_pause,
+            (joystick_input, gamepad_buttons).run_if(not(resource_exists::<ReplayPlayback>)),
+        ),
+    );
 }
 
 pub fn joystick_input(
@@ -37,6 +44,14 @@ pub fn gamepad_buttons(mut writer: MessageWriter<PlayerInput>, gamepads: Query<&
         if gamepad.just_pressed(GamepadButton::South) {
             writer.write(PlayerInput::Jump);
         }
+        if gamepad.just_pressed(GamepadButton::West) {
+            writer.write(PlayerInput::Activate);
+        }
+    }
+}
+
+pub fn gamepad_pause(mut writer: MessageWriter<PlayerInput>, gamepads: Query<&Gamepad>) {
+    for gamepad in gamepads {
         if gamepad.just_pressed(GamepadButton::Start) {
             writer.write(PlayerInput::Pause { toggle: true });
         }
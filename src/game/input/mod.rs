@@ -43,6 +43,7 @@ pub enum PlayerInput {
     Zoom(f32),
     Movement(Vec2),
     Jump,
+    Activate,
     Pause { toggle: bool },
     ToggleGizmos,
     Screenshot,
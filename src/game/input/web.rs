@@ -1,6 +1,7 @@
 use super::{PlayerInput, desktop};
 use crate::game::CANVAS_ID;
 use crate::game::game_state::GameState;
+use crate::game::replay::ReplayPlayback;
 use crate::or_return;
 use bevy::input::keyboard::Key;
 use bevy::prelude::*;
@@ -18,8 +19,8 @@ pub fn build(app: &mut App) {
                 pause_on_lose_focus,
                 pause_play,
                 desktop::fn_key_input,
-                desktop::keyboard_input,
-                desktop::mouse_input,
+                (desktop::keyboard_input, desktop::mouse_input)
+                    .run_if(not(resource_exists::<ReplayPlayback>)),
                 desktop::mouse_scroll,
             ),
         );
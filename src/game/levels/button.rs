@@ -1,10 +1,14 @@
 //! World buttons that open doors.
 //!
-//! Note: this should be replaced by a level scene with an animation and a script at some point.
+//! A button senses its pressers with a [`SignalVolume`] above the plate. Buttons can also emit a
+//! signal while pressed, and doors can require signals, see [`crate::game::levels::signal`].
 
 use crate::game::assets::preload::Preloads;
-use crate::game::levels::LevelObject;
-use crate::game::state::AppState;
+use crate::game::levels::signal::{
+    Sensed, SignalEmitter, SignalSystems, SignalVolume, Signals, detect_volumes,
+};
+use crate::game::levels::{DynamicLevelObject, LevelObject};
+use crate::game::logic::Player;
 use avian3d::prelude::*;
 use bevy::ecs::lifecycle::HookContext;
 use bevy::ecs::world::DeferredWorld;
@@ -22,10 +26,19 @@ pub struct ButtonPlugin;
 
 impl Plugin for ButtonPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, detect_button_press.run_if(in_state(AppState::Game)));
+        app.add_systems(
+            Update,
+            (
+                detect_button_press
+                    .after(detect_volumes)
+                    .in_set(SignalSystems::Sense),
+                move_button_doors.in_set(SignalSystems::React),
+            ),
+        );
     }
 }
 
+/// Goes on the rigid body, colliders on its children press buttons for it.
 #[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
 #[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
 pub struct ButtonPresser;
@@ -35,12 +48,88 @@ pub struct ButtonPresser;
 #[component(storage = "SparseSet")]
 pub struct PressedButton;
 
+/// Named group of pressers, so buttons can be pressed by only some objects.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Hash, Component)]
+pub struct PresserGroup(pub String);
+
+/// Which pressers a button reacts to.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+pub enum PresserFilter {
+    #[default]
+    Any,
+    Player,
+    /// Anything but the player.
+    Dyn,
+    Group(String),
+}
+
+impl PresserFilter {
+    pub fn accepts(&self, is_player: bool, group: Option<&PresserGroup>) -> bool {
+        match self {
+            PresserFilter::Any => true,
+            PresserFilter::Player => is_player,
+            PresserFilter::Dyn => !is_player,
+            PresserFilter::Group(name) => group.is_some_and(|group| group.0 == *name),
+        }
+    }
+}
+
+/// How long a button stays down.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub enum ButtonMode {
+    /// Only while something is on it.
+    #[default]
+    Momentary,
+    /// For the rest of the attempt once pressed.
+    Latch,
+    /// For this many seconds after it's released.
+    Timed(f32),
+}
+
+impl ButtonMode {
+    /// Whether the button is down, `held` is how many more seconds it stays down after release.
+    pub fn hold(self, on_plate: bool, held: &mut f32, delta: f32) -> bool {
+        match self {
+            ButtonMode::Momentary => on_plate,
+            ButtonMode::Latch => {
+                if on_plate {
+                    *held = f32::INFINITY;
+                }
+                *held > 0.0
+            }
+            ButtonMode::Timed(seconds) => {
+                if on_plate {
+                    *held = seconds;
+                } else {
+                    *held = (*held - delta).max(0.0);
+                }
+                on_plate || *held > 0.0
+            }
+        }
+    }
+}
+
+/// What it takes to press a [`LevelButton`].
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+pub struct ButtonPress {
+    pub pressed_by: PresserFilter,
+    /// Total mass of the accepted pressers needed to push the button down.
+    pub min_mass: Option<f32>,
+    pub mode: ButtonMode,
+    pub held: f32,
+}
+
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
 #[reflect(Debug, Clone, PartialEq, Hash, Component)]
 #[require(
-    LevelObject,
+    DynamicLevelObject,
     Transform,
     InheritedVisibility,
+    ButtonPress,
     PlaybackSettings = PlaybackSettings::REMOVE.with_spatial(true)
 )]
 #[component(on_insert = level_button_on_insert)]
@@ -73,38 +162,91 @@ pub struct LevelButtonPlate {
     Transform,
     RigidBody::Static,
     Collider::cuboid(0.54, 0.08, 0.54),
-    Sensor,
-    CollidingEntities::default()
+    SignalVolume::sensing(Sensed::Presser)
 )]
 pub struct LevelButtonSensor {
     pub prev_sensor_pressed: bool,
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
-#[reflect(Debug, Default, Clone, PartialEq, Component)]
-#[require(LevelObject, Transform)]
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[require(DynamicLevelObject, Transform)]
 pub struct LevelButtonDoor {
     pub default_trans: Transform,
     pub open_trans: Transform,
     pub openness: f32,
+    pub requires: ButtonRequirement,
+    pub open: bool,
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
-#[reflect(Debug, Clone, PartialEq, Hash, Component)]
-#[relationship(relationship_target = ControlledObjects)]
-pub struct ControlledBy(pub Entity);
-
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
-#[reflect(Debug, Clone, PartialEq, Hash, Component)]
-#[relationship_target(relationship = ControlledBy)]
-pub struct ControlledObjects(Vec<Entity>);
+/// Which buttons need to be pressed, or signals need to be on, for a door to open.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+#[reflect(no_field_bounds)]
+pub enum ButtonRequirement {
+    Button(Entity),
+    Signal(String),
+    All(Vec<ButtonRequirement>),
+    Any(Vec<ButtonRequirement>),
+    /// Met unless all of its inputs are met.
+    Not(Vec<ButtonRequirement>),
+    /// Switches between met and unmet every time its inputs become met.
+    Toggle {
+        inputs: Vec<ButtonRequirement>,
+        was_met: bool,
+        on: bool,
+    },
+    /// Stays met once its inputs have been met.
+    Latch {
+        inputs: Vec<ButtonRequirement>,
+        latched: bool,
+    },
+}
 
-impl ControlledObjects {
-    pub fn controlled(&self) -> &[Entity] {
-        &self.0
+impl ButtonRequirement {
+    pub fn evaluate(&mut self, pressed: &impl Fn(Entity) -> bool, signals: &Signals) -> bool {
+        match self {
+            ButtonRequirement::Button(button) => pressed(*button),
+            ButtonRequirement::Signal(signal) => signals.is_on(signal),
+            ButtonRequirement::All(inputs) => evaluate_all(inputs, pressed, signals),
+            ButtonRequirement::Any(inputs) => inputs
+                .iter_mut()
+                .map(|input| input.evaluate(pressed, signals))
+                .fold(false, |any, met| any || met),
+            ButtonRequirement::Not(inputs) => !evaluate_all(inputs, pressed, signals),
+            ButtonRequirement::Toggle {
+                inputs,
+                was_met,
+                on,
+            } => {
+                let met = evaluate_all(inputs, pressed, signals);
+                if met && !*was_met {
+                    *on = !*on;
+                }
+                *was_met = met;
+                *on
+            }
+            ButtonRequirement::Latch { inputs, latched } => {
+                *latched |= evaluate_all(inputs, pressed, signals);
+                *latched
+            }
+        }
     }
 }
 
+// every input is evaluated, rather than stopping at the first unmet one, so that nested toggles
+// see every press
+fn evaluate_all(
+    inputs: &mut [ButtonRequirement],
+    pressed: &impl Fn(Entity) -> bool,
+    signals: &Signals,
+) -> bool {
+    inputs
+        .iter_mut()
+        .map(|input| input.evaluate(pressed, signals))
+        .fold(true, |all, met| all && met)
+}
+
 fn level_button_on_insert(mut world: DeferredWorld, ctx: HookContext) {
     let preloads = world
         .get_resource::<Preloads>()
@@ -185,18 +327,19 @@ fn level_button_plate_on_scene_load(
 
 fn detect_button_press(
     mut cmd: Commands,
-    buttons: Query<Entity, With<LevelButton>>,
+    mut buttons: Query<(Entity, &mut ButtonPress, Option<&mut SignalEmitter>), With<LevelButton>>,
     children: Query<&Children>,
-    controlled: Query<&ControlledObjects>,
-    mut button_plates: Query<(&mut LevelButtonPlate, &mut Transform), Without<LevelButtonDoor>>,
+    mut button_plates: Query<(&mut LevelButtonPlate, &mut Transform)>,
     mut mesh_materials: Query<&mut MeshMaterial3d<StandardMaterial>>,
-    mut button_sensors: Query<(&CollidingEntities, &mut LevelButtonSensor)>,
-    button_pressers: Query<(), With<ButtonPresser>>,
-    mut button_doors: Query<(&mut LevelButtonDoor, &mut Transform), Without<LevelButtonPlate>>,
+    mut button_sensors: Query<(&SignalVolume, &mut LevelButtonSensor)>,
+    button_pressers: Query<
+        (Has<Player>, Option<&PresserGroup>, Option<&ComputedMass>),
+        With<ButtonPresser>,
+    >,
     time: Res<Time>,
     preloads: Res<Preloads>,
 ) {
-    for button in buttons {
+    for (button, mut press, emitter) in buttons.iter_mut() {
         let Some(plate_entity) = children
             .get(button)
             .iter()
@@ -216,7 +359,7 @@ fn detect_button_press(
         else {
             continue;
         };
-        let (sensor_collisions, mut button_sensor) = button_sensors
+        let (sensor_volume, mut button_sensor) = button_sensors
             .get_mut(sensor_entity)
             .expect("button_sensors does not have a contained entity");
         let LevelButtonPlate {
@@ -227,13 +370,20 @@ fn detect_button_press(
             depression,
         } = &mut *button_plate;
 
-        let mut sensor_pressed = false;
-        for entity in sensor_collisions.iter() {
-            if button_pressers.contains(*entity) {
-                sensor_pressed = true;
-                break;
+        let mut on_plate = false;
+        let mut mass = 0.0;
+        for (is_player, group, computed_mass) in
+            button_pressers.iter_many(sensor_volume.inside.iter())
+        {
+            if press.pressed_by.accepts(is_player, group) {
+                on_plate = true;
+                mass += computed_mass.map_or(0.0, ComputedMass::value);
             }
         }
+        let on_plate = on_plate && press.min_mass.is_none_or(|min_mass| mass >= min_mass);
+
+        let ButtonPress { mode, held, .. } = &mut *press;
+        let sensor_pressed = mode.hold(on_plate, held, time.delta_secs());
 
         if sensor_pressed {
             *depression = (*depression + time.delta_secs() * BUTTON_DEPRESSION_SPEED).min(1.0);
@@ -248,7 +398,11 @@ fn detect_button_press(
             commands.remove::<(AudioPlayer, SpatialAudioSink, PlaybackSettings)>();
 
             if sensor_pressed {
-                commands.insert((AudioPlayer::new(preloads.button_on()), PLAYBACK_SETTINGS));
+                commands.insert((
+                    PressedButton,
+                    AudioPlayer::new(preloads.button_on()),
+                    PLAYBACK_SETTINGS,
+                ));
                 set_material(
                     plate_entity,
                     &depressed_material,
@@ -256,6 +410,7 @@ fn detect_button_press(
                     &mut mesh_materials,
                 );
             } else {
+                commands.remove::<PressedButton>();
                 commands.insert((AudioPlayer::new(preloads.button_off()), PLAYBACK_SETTINGS));
                 set_material(
                     plate_entity,
@@ -266,38 +421,47 @@ fn detect_button_press(
             }
         }
 
-        for door_entity in controlled
-            .get(button)
-            .iter()
-            .flat_map(|controlled| controlled.controlled().iter())
-            .copied()
-        {
-            if let Ok((mut door, mut door_trans)) = button_doors.get_mut(door_entity) {
-                if sensor_pressed {
-                    door.openness = (door.openness + time.delta_secs() * DOOR_SLIDE_SPEED).min(1.0);
-                } else {
-                    door.openness = (door.openness - time.delta_secs() * DOOR_SLIDE_SPEED).max(0.0);
-                }
+        if let Some(mut emitter) = emitter {
+            emitter.on = sensor_pressed;
+        }
+
+        button_sensor.prev_sensor_pressed = sensor_pressed;
+    }
+}
+
+fn move_button_doors(
+    mut cmd: Commands,
+    mut button_doors: Query<(Entity, &mut LevelButtonDoor, &mut Transform)>,
+    pressed_buttons: Query<(), With<PressedButton>>,
+    signals: Res<Signals>,
+    time: Res<Time>,
+    preloads: Res<Preloads>,
+) {
+    for (door_entity, mut door, mut door_trans) in button_doors.iter_mut() {
+        let open = door
+            .requires
+            .evaluate(&|button| pressed_buttons.contains(button), &signals);
 
-                *door_trans =
-                    Transform::interpolate(&door.default_trans, &door.open_trans, door.openness);
+        if open {
+            door.openness = (door.openness + time.delta_secs() * DOOR_SLIDE_SPEED).min(1.0);
+        } else {
+            door.openness = (door.openness - time.delta_secs() * DOOR_SLIDE_SPEED).max(0.0);
+        }
 
-                if button_sensor.prev_sensor_pressed != sensor_pressed {
-                    let mut commands = cmd.entity(door_entity);
-                    commands.remove::<(AudioPlayer, SpatialAudioSink, PlaybackSettings)>();
+        *door_trans = Transform::interpolate(&door.default_trans, &door.open_trans, door.openness);
 
-                    if sensor_pressed {
-                        commands
-                            .insert((AudioPlayer::new(preloads.door_open()), PLAYBACK_SETTINGS));
-                    } else {
-                        commands
-                            .insert((AudioPlayer::new(preloads.door_close()), PLAYBACK_SETTINGS));
-                    }
-                }
+        if door.open != open {
+            let mut commands = cmd.entity(door_entity);
+            commands.remove::<(AudioPlayer, SpatialAudioSink, PlaybackSettings)>();
+
+            if open {
+                commands.insert((AudioPlayer::new(preloads.door_open()), PLAYBACK_SETTINGS));
+            } else {
+                commands.insert((AudioPlayer::new(preloads.door_close()), PLAYBACK_SETTINGS));
             }
         }
 
-        button_sensor.prev_sensor_pressed = sensor_pressed;
+        door.open = open;
     }
 }
 
@@ -313,3 +477,67 @@ fn set_material(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requirement_all_any_not() {
+        let signals = Signals::default();
+        let a = Entity::from_raw_u32(1).unwrap();
+        let b = Entity::from_raw_u32(2).unwrap();
+        let mut all = ButtonRequirement::All(vec![
+            ButtonRequirement::Button(a),
+            ButtonRequirement::Button(b),
+        ]);
+        let mut any = ButtonRequirement::Any(vec![
+            ButtonRequirement::Button(a),
+            ButtonRequirement::Button(b),
+        ]);
+        let mut not = ButtonRequirement::Not(vec![ButtonRequirement::Button(a)]);
+
+        assert!(!all.evaluate(&|button| button == a, &signals));
+        assert!(all.evaluate(&|_| true, &signals));
+        assert!(any.evaluate(&|button| button == b, &signals));
+        assert!(!any.evaluate(&|_| false, &signals));
+        assert!(not.evaluate(&|button| button == b, &signals));
+        assert!(!not.evaluate(&|button| button == a, &signals));
+    }
+
+    #[test]
+    fn test_requirement_toggle_latch() {
+        let signals = Signals::default();
+        let a = Entity::from_raw_u32(1).unwrap();
+        let mut toggle = ButtonRequirement::Toggle {
+            inputs: vec![ButtonRequirement::Button(a)],
+            was_met: false,
+            on: false,
+        };
+        let mut latch = ButtonRequirement::Latch {
+            inputs: vec![ButtonRequirement::Button(a)],
+            latched: false,
+        };
+
+        // press, hold, release, press again
+        let presses = [true, true, false, true];
+        let toggled = presses.map(|pressed| toggle.evaluate(&|_| pressed, &signals));
+        let latched = presses.map(|pressed| latch.evaluate(&|_| pressed, &signals));
+
+        assert_eq!(toggled, [true, true, true, false]);
+        assert_eq!(latched, [true, true, true, true]);
+    }
+
+    #[test]
+    fn test_button_mode_hold() {
+        let on_plate = [true, false, false, false];
+        let hold = |mode: ButtonMode| {
+            let mut held = 0.0;
+            on_plate.map(|on_plate| mode.hold(on_plate, &mut held, 1.0))
+        };
+
+        assert_eq!(hold(ButtonMode::Momentary), [true, false, false, false]);
+        assert_eq!(hold(ButtonMode::Latch), [true, true, true, true]);
+        assert_eq!(hold(ButtonMode::Timed(2.5)), [true, true, true, false]);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::game::levels::LevelObject;
+use crate::game::logic::Player;
+use crate::game::state::AppState;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct CheckpointPlugin;
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveCheckpoint>()
+            .add_observer(detect_checkpoint)
+            .add_systems(OnExit(AppState::Game), clear_checkpoint);
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Component, Reflect)]
+#[require(LevelObject, Transform, Sensor, CollisionEventsEnabled)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+pub struct Checkpoint {
+    pub respawn: Transform,
+}
+
+/// The respawn point of the last checkpoint the player touched, if any.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Resource, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Resource)]
+pub struct ActiveCheckpoint(pub Option<Transform>);
+
+impl ActiveCheckpoint {
+    pub fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+fn detect_checkpoint(
+    collision: On<CollisionStart>,
+    query: Query<&Checkpoint>,
+    player_query: Query<(), With<Player>>,
+    mut active: ResMut<ActiveCheckpoint>,
+) {
+    let Ok(checkpoint) = query.get(collision.collider1) else {
+        return;
+    };
+
+    if player_query.contains(collision.collider2) && active.0 != Some(checkpoint.respawn) {
+        info!("Checkpoint reached");
+        active.0 = Some(checkpoint.respawn);
+    }
+}
+
+fn clear_checkpoint(mut active: ResMut<ActiveCheckpoint>) {
+    active.0 = None;
+}
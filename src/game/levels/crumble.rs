@@ -0,0 +1,289 @@
+//! Platforms that crumble away under the player, or shatter when hit hard enough. They are
+//! static level objects, so restarting the level always brings them back.
+
+use crate::game::game_state::GameState;
+use crate::game::levels::LevelObject;
+use crate::game::levels::gravity::GravityUp;
+use crate::game::logic::Player;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+/// How far a crumbling platform's mesh shakes from its place.
+pub const SHAKE_DISTANCE: f32 = 0.04;
+/// Seconds before the pieces of a shattered platform disappear.
+pub const FRAGMENT_LIFETIME: f32 = 3.0;
+/// How fast the pieces of a shattered platform fly apart.
+pub const FRAGMENT_SPEED: f32 = 2.0;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct CrumblePlugin;
+
+impl Plugin for CrumblePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_crumble_collide).add_systems(
+            Update,
+            (crumble_platforms, expire_fragments).run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// What sets a platform off.
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum CrumbleTrigger {
+    /// The player landing on it, after shaking for `delay` seconds.
+    Landing { delay: f32 },
+    /// Anything hitting it at least this fast.
+    Impact { threshold: f32 },
+}
+
+/// What happens to a platform once it goes.
+#[derive(
+    Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Reflect, strum::VariantArray, strum::Display,
+)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+#[strum(serialize_all = "snake_case")]
+pub enum CrumbleEnd {
+    /// Turns dynamic and falls.
+    #[default]
+    Fall,
+    Vanish,
+    /// Vanishes, leaving pieces behind.
+    Shatter,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub enum CrumbleState {
+    #[default]
+    Solid,
+    Shaking {
+        remaining: f32,
+    },
+    /// Comes back after `remaining` seconds, if it respawns at all.
+    Gone {
+        remaining: Option<f32>,
+    },
+}
+
+/// A cuboid platform, its mesh is a child so it can shake without moving the collider.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[require(LevelObject, CollisionEventsEnabled)]
+pub struct Crumbling {
+    pub trigger: CrumbleTrigger,
+    pub end: CrumbleEnd,
+    /// Seconds until the platform comes back.
+    pub respawn: Option<f32>,
+    pub dimensions: Vec3,
+    /// Where the platform returns to when it respawns.
+    pub home: Transform,
+    pub state: CrumbleState,
+}
+
+impl Crumbling {
+    /// Starts the platform going, unless it already has.
+    pub fn set_off(&mut self) {
+        if self.state == CrumbleState::Solid {
+            let remaining = match self.trigger {
+                CrumbleTrigger::Landing { delay } => delay,
+                CrumbleTrigger::Impact { .. } => 0.0,
+            };
+            self.state = CrumbleState::Shaking { remaining };
+        }
+    }
+
+    /// Advances the platform's state, returning it if it changed.
+    pub fn tick(&mut self, delta: f32) -> Option<CrumbleState> {
+        match &mut self.state {
+            CrumbleState::Solid | CrumbleState::Gone { remaining: None } => return None,
+            CrumbleState::Shaking { remaining } => {
+                *remaining -= delta;
+                if *remaining > 0.0 {
+                    return None;
+                }
+                self.state = CrumbleState::Gone {
+                    remaining: self.respawn,
+                };
+            }
+            CrumbleState::Gone {
+                remaining: Some(remaining),
+            } => {
+                *remaining -= delta;
+                if *remaining > 0.0 {
+                    return None;
+                }
+                self.state = CrumbleState::Solid;
+            }
+        }
+        Some(self.state)
+    }
+}
+
+/// A piece of a shattered platform.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+#[require(LevelObject)]
+pub struct Fragment {
+    pub remaining: f32,
+}
+
+fn on_crumble_collide(
+    e: On<CollisionStart>,
+    mut platforms: Query<&mut Crumbling>,
+    players: Query<&GravityUp, With<Player>>,
+    velocities: Query<&LinearVelocity>,
+    collisions: Collisions,
+) {
+    let Ok(mut platform) = platforms.get_mut(e.collider1) else {
+        return;
+    };
+
+    match platform.trigger {
+        CrumbleTrigger::Landing { .. } => {
+            let Ok(up) = players.get(e.collider2) else {
+                return;
+            };
+            let landed = collisions
+                .get(e.collider1, e.collider2)
+                .is_some_and(|pair| {
+                    pair.manifolds.iter().any(|manifold| {
+                        let normal = if pair.collider1 == e.collider2 {
+                            -manifold.normal
+                        } else {
+                            manifold.normal
+                        };
+                        normal.angle_between(*up.0) <= PI / 3.0
+                    })
+                });
+            if landed {
+                platform.set_off();
+            }
+        }
+        CrumbleTrigger::Impact { threshold } => {
+            let speed = e
+                .body2
+                .and_then(|body| velocities.get(body).ok())
+                .map_or(0.0, |velocity| velocity.length());
+            if speed >= threshold {
+                platform.set_off();
+            }
+        }
+    }
+}
+
+fn crumble_platforms(
+    mut cmd: Commands,
+    platforms: Query<(Entity, &mut Crumbling, &Children)>,
+    mut meshes: Query<(&mut Transform, &MeshMaterial3d<StandardMaterial>)>,
+    mut mesh_assets: ResMut<Assets<Mesh>>,
+    time: Res<Time>,
+) {
+    for (entity, mut platform, children) in platforms {
+        let Some(&child) = children.first() else {
+            continue;
+        };
+        let Ok((mut mesh_trans, material)) = meshes.get_mut(child) else {
+            continue;
+        };
+
+        if let CrumbleState::Shaking { .. } = platform.state {
+            let t = time.elapsed_secs() * 40.0;
+            mesh_trans.translation = Vec3::new(t.sin(), 0.0, (t * 1.3).cos()) * SHAKE_DISTANCE;
+        }
+
+        let Some(state) = platform.tick(time.delta_secs()) else {
+            continue;
+        };
+        mesh_trans.translation = Vec3::ZERO;
+
+        match (state, platform.end) {
+            (CrumbleState::Solid, _) => {
+                cmd.entity(entity).remove::<ColliderDisabled>().insert((
+                    RigidBody::Static,
+                    platform.home,
+                    LinearVelocity::ZERO,
+                    AngularVelocity::ZERO,
+                    Visibility::Inherited,
+                ));
+            }
+            (_, CrumbleEnd::Fall) => {
+                cmd.entity(entity).insert(RigidBody::Dynamic);
+            }
+            (_, CrumbleEnd::Vanish) => {
+                cmd.entity(entity)
+                    .insert((ColliderDisabled, Visibility::Hidden));
+            }
+            (_, CrumbleEnd::Shatter) => {
+                cmd.entity(entity)
+                    .insert((ColliderDisabled, Visibility::Hidden));
+
+                let size = platform.dimensions / 2.0;
+                let piece = mesh_assets.add(Cuboid::from_size(size));
+                for corner in 0..8 {
+                    let offset = Vec3::new(
+                        if corner & 1 == 0 { -0.5 } else { 0.5 },
+                        if corner & 2 == 0 { -0.5 } else { 0.5 },
+                        if corner & 4 == 0 { -0.5 } else { 0.5 },
+                    );
+                    let direction = platform.home.rotation * offset;
+                    cmd.spawn((
+                        Fragment {
+                            remaining: FRAGMENT_LIFETIME,
+                        },
+                        platform
+                            .home
+                            .with_translation(platform.home.transform_point(offset * size)),
+                        Mesh3d(piece.clone()),
+                        MeshMaterial3d(material.0.clone()),
+                        RigidBody::Dynamic,
+                        Collider::cuboid(size.x, size.y, size.z),
+                        LinearVelocity(direction.normalize() * FRAGMENT_SPEED),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn expire_fragments(mut cmd: Commands, fragments: Query<(Entity, &mut Fragment)>, time: Res<Time>) {
+    for (entity, mut fragment) in fragments {
+        fragment.remaining -= time.delta_secs();
+        if fragment.remaining <= 0.0 {
+            cmd.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crumble_respawn() {
+        let mut platform = Crumbling {
+            trigger: CrumbleTrigger::Landing { delay: 0.5 },
+            end: CrumbleEnd::Vanish,
+            respawn: Some(2.0),
+            dimensions: Vec3::ONE,
+            home: Transform::default(),
+            state: CrumbleState::Solid,
+        };
+
+        assert_eq!(platform.tick(1.0), None);
+        platform.set_off();
+        assert_eq!(platform.tick(0.25), None);
+        assert_eq!(
+            platform.tick(0.25),
+            Some(CrumbleState::Gone {
+                remaining: Some(2.0)
+            })
+        );
+
+        // landing again while gone does nothing
+        platform.set_off();
+        assert_eq!(platform.tick(1.0), None);
+        assert_eq!(platform.tick(1.0), Some(CrumbleState::Solid));
+    }
+}
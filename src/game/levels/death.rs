@@ -1,18 +1,33 @@
+use crate::game::game_state::GameState;
+use crate::game::levels::signal::{Sensed, SignalAction, SignalActions, SignalVolume};
+use crate::game::state::AppState;
 use avian3d::prelude::*;
 use bevy::prelude::*;
+use std::collections::HashSet;
 
 #[derive(Default)]
 pub struct DeathPlugin;
 
 impl Plugin for DeathPlugin {
     fn build(&self, app: &mut App) {
-        app.add_message::<Kill>().add_observer(on_killable_collide);
+        app.add_message::<Kill>()
+            .add_observer(respawn_killed)
+            .add_systems(
+                Update,
+                (kill_out_of_bounds, dispatch_kills)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(AppState::Game), clear_bounds);
     }
 }
 
 #[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
 #[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
-#[require(Sensor, CollisionEventsEnabled)]
+#[require(
+    SignalVolume::sensing(Sensed::Killable),
+    SignalActions::local(vec![SignalAction::Kill])
+)]
 pub struct DeathCollider;
 
 #[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
@@ -33,17 +48,106 @@ impl Kill {
     }
 }
 
+/// Triggered once per frame for each [`Killable`] entity with a [`Kill`] message. Whatever the
+/// entity is decides what dying does to it.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, EntityEvent, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Hash)]
+pub struct Killed {
+    pub entity: Entity,
+}
+
 #[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Event, Reflect)]
 #[reflect(Debug, Clone, PartialEq, Hash)]
 pub struct PlayerDiedEvent;
 
-fn on_killable_collide(
-    e: On<CollisionStart>,
-    colliders: Query<(), With<DeathCollider>>,
-    killables: Query<(), With<Killable>>,
+/// Puts a body back where it started when it's killed.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+#[require(Killable)]
+pub struct Respawn {
+    pub home: Transform,
+}
+
+/// Anything [`Killable`] outside of the bounds is killed.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Resource, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Resource)]
+pub struct LevelBounds {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl LevelBounds {
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+}
+
+fn kill_out_of_bounds(
+    killables: Query<(Entity, &GlobalTransform), With<Killable>>,
+    bounds: Option<Res<LevelBounds>>,
     mut kill_msg: MessageWriter<Kill>,
 ) {
-    if colliders.contains(e.collider1) && killables.contains(e.collider2) {
-        kill_msg.write(Kill::new(e.collider2));
+    let Some(bounds) = bounds else {
+        return;
+    };
+
+    for (entity, trans) in killables {
+        if !bounds.contains(trans.translation()) {
+            kill_msg.write(Kill::new(entity));
+        }
+    }
+}
+
+fn dispatch_kills(
+    mut cmd: Commands,
+    mut kill_msg: MessageReader<Kill>,
+    killables: Query<(), With<Killable>>,
+) {
+    let killed = kill_msg
+        .read()
+        .map(|kill| kill.to_kill)
+        .filter(|entity| killables.contains(*entity))
+        .collect::<HashSet<_>>();
+
+    for entity in killed {
+        cmd.trigger(Killed { entity });
+    }
+}
+
+fn respawn_killed(
+    killed: On<Killed>,
+    mut bodies: Query<(
+        &Respawn,
+        &mut Transform,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+    )>,
+) {
+    if let Ok((respawn, mut trans, mut linear, mut angular)) = bodies.get_mut(killed.entity) {
+        *trans = respawn.home;
+        linear.0 = Vec3::ZERO;
+        angular.0 = Vec3::ZERO;
+    }
+}
+
+fn clear_bounds(mut cmd: Commands) {
+    cmd.remove_resource::<LevelBounds>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_contains() {
+        let bounds = LevelBounds {
+            min: Vec3::new(-10.0, -5.0, -50.0),
+            max: Vec3::new(10.0, 20.0, 5.0),
+        };
+
+        assert!(bounds.contains(Vec3::ZERO));
+        assert!(bounds.contains(Vec3::new(10.0, -5.0, -50.0)));
+        assert!(!bounds.contains(Vec3::new(0.0, -6.0, 0.0)));
+        assert!(!bounds.contains(Vec3::new(0.0, 0.0, 6.0)));
     }
 }
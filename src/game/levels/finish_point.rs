@@ -2,7 +2,9 @@ use crate::game::assets::fonts::FontNames;
 use crate::game::assets::preload::Preloads;
 use crate::game::game_state::GameState;
 use crate::game::levels::LevelObject;
-use crate::game::logic::Player;
+use crate::game::levels::gem::GemCount;
+use crate::game::levels::signal::{SignalAction, SignalActions, SignalVolume};
+use crate::game::timer::LevelTimer;
 use avian3d::prelude::*;
 use bevy::ecs::lifecycle::HookContext;
 use bevy::ecs::world::DeferredWorld;
@@ -14,8 +16,7 @@ pub struct FinishPointPlugin;
 
 impl Plugin for FinishPointPlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(detect_level_finish)
-            .add_systems(Update, spin_finish_sign);
+        app.add_systems(Update, (spin_finish_sign, update_finish_hint));
     }
 }
 
@@ -23,9 +24,9 @@ impl Plugin for FinishPointPlugin {
 #[require(
     LevelObject,
     Transform,
-    Sensor,
-    Collider::cuboid(1.0, 1.0, 1.0),
-    CollisionEventsEnabled
+    SignalVolume,
+    SignalActions::local(vec![SignalAction::Finish]),
+    Collider::cuboid(1.0, 1.0, 1.0)
 )]
 #[component(on_insert = finish_point_on_insert)]
 #[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
@@ -35,6 +36,11 @@ pub struct FinishPoint;
 #[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
 pub struct FinishLabel;
 
+/// Shown above the [`FinishLabel`] while more gems are needed, holds the count it's showing.
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+pub struct FinishHint(pub u32);
+
 fn finish_point_on_insert(mut world: DeferredWorld, ctx: HookContext) {
     let preloads = world
         .get_resource::<Preloads>()
@@ -48,35 +54,57 @@ fn finish_point_on_insert(mut world: DeferredWorld, ctx: HookContext) {
         .get(&font.id())
         .expect("missing font name for required font")
         .clone();
+    let hint_font = preloads.text_font();
+    let hint_font = font_names
+        .get(&hint_font.id())
+        .expect("missing font name for required font")
+        .clone();
     let material = preloads.glow_text_material();
     let mut binding = world.commands();
     let mut commands = binding.entity(ctx.entity);
     commands.insert_if_new(SceneRoot(level_end));
-    commands.with_child((
-        FinishLabel,
-        Transform::from_xyz(0.0, 0.65, 0.0),
-        Text3d::new("Finish"),
-        Text3dStyling {
-            font: font.into(),
-            size: 64.0,
-            world_scale: Some(Vec2::splat(0.15)),
-            ..default()
-        },
-        Mesh3d::default(),
-        MeshMaterial3d(material),
-    ));
+    commands.with_children(|parent| {
+        parent
+            .spawn((
+                FinishLabel,
+                Transform::from_xyz(0.0, 0.65, 0.0),
+                Text3d::new("Finish"),
+                Text3dStyling {
+                    font: font.into(),
+                    size: 64.0,
+                    world_scale: Some(Vec2::splat(0.15)),
+                    ..default()
+                },
+                Mesh3d::default(),
+                MeshMaterial3d(material.clone()),
+            ))
+            .with_child((
+                FinishHint::default(),
+                Transform::from_xyz(0.0, 0.3, 0.0),
+                Visibility::Hidden,
+                Text3d::new(""),
+                Text3dStyling {
+                    font: hint_font.into(),
+                    size: 64.0,
+                    world_scale: Some(Vec2::splat(0.08)),
+                    ..default()
+                },
+                Mesh3d::default(),
+                MeshMaterial3d(material),
+            ));
+    });
 }
 
-fn detect_level_finish(
-    collision: On<CollisionStart>,
-    query: Query<(), With<FinishPoint>>,
-    player_query: Query<(), With<Player>>,
-    mut state: ResMut<NextState<GameState>>,
-) {
-    if query.contains(collision.collider1) && player_query.contains(collision.collider2) {
-        info!("Level finished");
-        state.set(GameState::Finished);
+/// Finishes the level, unless there are gems left to collect.
+pub fn finish_level(gems: &GemCount, state: &mut NextState<GameState>, timer: &mut LevelTimer) {
+    if !gems.is_complete() {
+        info!("Finish locked, {} more gems needed", gems.remaining());
+        return;
     }
+
+    info!("Level finished");
+    timer.stop();
+    state.set(GameState::Finished);
 }
 
 fn spin_finish_sign(query: Query<&mut Transform, With<FinishLabel>>, time: Res<Time>) {
@@ -84,3 +112,26 @@ fn spin_finish_sign(query: Query<&mut Transform, With<FinishLabel>>, time: Res<T
         trans.rotation = Quat::from_rotation_y(time.elapsed_secs_wrapped());
     }
 }
+
+fn update_finish_hint(
+    query: Query<(&mut FinishHint, &mut Text3d, &mut Visibility)>,
+    gems: Res<GemCount>,
+) {
+    let remaining = gems.remaining();
+    for (mut hint, mut text, mut visibility) in query {
+        if hint.0 != remaining {
+            hint.0 = remaining;
+            *text = match remaining {
+                1 => Text3d::new("1 more gem"),
+                _ => Text3d::new(format!("{remaining} more gems")),
+            };
+        }
+
+        let target = if remaining > 0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(target);
+    }
+}
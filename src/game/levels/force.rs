@@ -0,0 +1,184 @@
+//! Sensor volumes that push dynamic bodies around: wind, conveyors and attractors.
+//!
+//! Fields accelerate bodies the same way regardless of mass, like gravity does.
+
+use crate::game::game_state::GameState;
+use crate::game::levels::{LevelObject, colliding_bodies};
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+/// How quickly a conveyor brings things up to its speed.
+pub const CONVEYOR_GRIP: f32 = 6.0;
+
+const WIND_COLOR: Color = Color::srgb(0.4, 0.8, 1.0);
+const CONVEYOR_COLOR: Color = Color::srgb(1.0, 0.7, 0.2);
+const ATTRACTOR_COLOR: Color = Color::srgb(0.8, 0.3, 1.0);
+const REPULSOR_COLOR: Color = Color::srgb(1.0, 0.3, 0.4);
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ForceFieldPlugin;
+
+impl Plugin for ForceFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<ForceFieldGizmos>()
+            .add_systems(
+                FixedUpdate,
+                apply_force_fields.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, draw_force_fields);
+    }
+}
+
+/// Gizmos showing which way force fields push, toggled together with the physics gizmos.
+#[derive(
+    Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, GizmoConfigGroup, Reflect,
+)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+pub struct ForceFieldGizmos;
+
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[require(LevelObject, Transform, Sensor, CollidingEntities)]
+pub enum ForceField {
+    /// Pushes along `direction`, getting weaker downwind by `falloff` over `length`.
+    Wind {
+        direction: Dir3,
+        strength: f32,
+        falloff: f32,
+        length: f32,
+    },
+    /// Drags things along `direction` until they move at `speed`.
+    Conveyor { direction: Dir3, speed: f32 },
+    /// Pulls towards its center, getting weaker towards `radius`. Pushes away with a negative
+    /// strength.
+    Attractor { strength: f32, radius: f32 },
+}
+
+impl ForceField {
+    /// Acceleration of a body at `position`, directions are relative to `transform`.
+    pub fn acceleration(
+        &self,
+        transform: &GlobalTransform,
+        position: Vec3,
+        velocity: Vec3,
+    ) -> Vec3 {
+        let (_, rotation, center) = transform.to_scale_rotation_translation();
+        match *self {
+            ForceField::Wind {
+                direction,
+                strength,
+                falloff,
+                length,
+            } => {
+                let direction = rotation * direction;
+                let downwind = (position - center).dot(*direction) / length + 0.5;
+                direction * strength * (1.0 - falloff * downwind.clamp(0.0, 1.0))
+            }
+            ForceField::Conveyor { direction, speed } => {
+                let direction = rotation * direction;
+                direction * (speed - velocity.dot(*direction)) * CONVEYOR_GRIP
+            }
+            ForceField::Attractor { strength, radius } => {
+                let offset = center - position;
+                let closeness = 1.0 - (offset.length() / radius).min(1.0);
+                offset.normalize_or_zero() * strength * closeness
+            }
+        }
+    }
+}
+
+fn apply_force_fields(
+    fields: Query<(&ForceField, &GlobalTransform, &CollidingEntities)>,
+    colliders: Query<&ColliderOf>,
+    mut bodies: Query<(&RigidBody, &Position, &mut LinearVelocity)>,
+    time: Res<Time>,
+) {
+    for (field, transform, colliding) in fields {
+        for entity in colliding_bodies(colliding, &colliders) {
+            let Ok((body, position, mut velocity)) = bodies.get_mut(entity) else {
+                continue;
+            };
+            if body.is_dynamic() {
+                velocity.0 +=
+                    field.acceleration(transform, position.0, velocity.0) * time.delta_secs();
+            }
+        }
+    }
+}
+
+fn draw_force_fields(
+    mut gizmos: Gizmos<ForceFieldGizmos>,
+    fields: Query<(&ForceField, &GlobalTransform)>,
+) {
+    for (field, transform) in fields {
+        let (_, rotation, center) = transform.to_scale_rotation_translation();
+        match *field {
+            ForceField::Wind {
+                direction, length, ..
+            } => {
+                let half = rotation * direction * length / 2.0;
+                gizmos.arrow(center - half, center + half, WIND_COLOR);
+            }
+            ForceField::Conveyor { direction, .. } => {
+                let half = rotation * direction / 2.0;
+                gizmos.arrow(center - half, center + half, CONVEYOR_COLOR);
+            }
+            ForceField::Attractor { strength, radius } => {
+                let color = if strength >= 0.0 {
+                    ATTRACTOR_COLOR
+                } else {
+                    REPULSOR_COLOR
+                };
+                gizmos.sphere(Isometry3d::from_translation(center), radius, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wind_falloff() {
+        let wind = ForceField::Wind {
+            direction: Dir3::X,
+            strength: 10.0,
+            falloff: 0.5,
+            length: 4.0,
+        };
+        let transform = GlobalTransform::default();
+
+        let upwind = wind.acceleration(&transform, Vec3::new(-2.0, 0.0, 0.0), Vec3::ZERO);
+        let downwind = wind.acceleration(&transform, Vec3::new(2.0, 0.0, 0.0), Vec3::ZERO);
+        assert!(upwind.abs_diff_eq(Vec3::new(10.0, 0.0, 0.0), 1e-5));
+        assert!(downwind.abs_diff_eq(Vec3::new(5.0, 0.0, 0.0), 1e-5));
+    }
+
+    #[test]
+    fn test_conveyor_speed() {
+        let conveyor = ForceField::Conveyor {
+            direction: Dir3::NEG_Z,
+            speed: 2.0,
+        };
+        let transform = GlobalTransform::default();
+
+        let at_speed = conveyor.acceleration(&transform, Vec3::ZERO, Vec3::new(1.0, 0.0, -2.0));
+        assert!(at_speed.abs_diff_eq(Vec3::ZERO, 1e-5));
+    }
+
+    #[test]
+    fn test_attractor() {
+        let attractor = ForceField::Attractor {
+            strength: 4.0,
+            radius: 2.0,
+        };
+        let transform = GlobalTransform::from_translation(Vec3::Y);
+
+        let pull = attractor.acceleration(&transform, Vec3::ZERO, Vec3::ZERO);
+        assert!(pull.abs_diff_eq(Vec3::new(0.0, 2.0, 0.0), 1e-5));
+
+        let outside = attractor.acceleration(&transform, Vec3::new(0.0, 5.0, 0.0), Vec3::ZERO);
+        assert!(outside.abs_diff_eq(Vec3::ZERO, 1e-5));
+    }
+}
@@ -0,0 +1,92 @@
+//! Gems the player has to collect before the finish point unlocks.
+
+use crate::game::levels::DynamicLevelObject;
+use crate::game::levels::pickup::{Pickup, PickupTouched};
+use crate::game::state::AppState;
+use bevy::prelude::*;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct GemPlugin;
+
+impl Plugin for GemPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GemCount>()
+            .add_observer(collect_gem)
+            .add_systems(Update, count_gems.run_if(in_state(AppState::Game)))
+            .add_systems(OnExit(AppState::Game), clear_gems);
+    }
+}
+
+/// Picked up when the player touches it. Gems are dynamic level objects, so they stay collected
+/// when restarting from a checkpoint.
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+#[require(DynamicLevelObject, Pickup)]
+pub struct Gem;
+
+/// Gems collected in the current level.
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Resource, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Resource)]
+pub struct GemCount {
+    pub collected: u32,
+    pub total: u32,
+    /// How many gems unlock the finish point.
+    pub required: u32,
+}
+
+impl GemCount {
+    pub fn remaining(&self) -> u32 {
+        self.required.saturating_sub(self.collected)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.remaining() == 0
+    }
+}
+
+fn collect_gem(touched: On<PickupTouched>, mut gems: Query<&mut Pickup, With<Gem>>) {
+    if let Ok(mut pickup) = gems.get_mut(touched.entity) {
+        info!("Gem collected");
+        pickup.taken = true;
+    }
+}
+
+fn count_gems(gems: Query<&Pickup, With<Gem>>, mut count: ResMut<GemCount>) {
+    let mut collected = 0;
+    let mut total = 0;
+    for pickup in gems {
+        collected += u32::from(pickup.taken);
+        total += 1;
+    }
+
+    let required = count.required;
+    count.set_if_neq(GemCount {
+        collected,
+        total,
+        required,
+    });
+}
+
+fn clear_gems(mut count: ResMut<GemCount>) {
+    *count = GemCount::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining() {
+        let mut count = GemCount {
+            collected: 1,
+            total: 3,
+            required: 2,
+        };
+        assert_eq!(count.remaining(), 1);
+        assert!(!count.is_complete());
+
+        count.collected = 3;
+        assert_eq!(count.remaining(), 0);
+        assert!(count.is_complete());
+    }
+}
@@ -0,0 +1,137 @@
+//! Level-wide gravity and gravity zones that redirect it.
+//!
+//! Zones don't touch the global [`Gravity`], they make up the difference on the velocity of every
+//! dynamic body inside them instead.
+
+use crate::game::game_state::GameState;
+use crate::game::levels::{LevelObject, colliding_bodies};
+use crate::game::state::AppState;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct GravityPlugin;
+
+impl Plugin for GravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            apply_gravity_zones.run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnExit(AppState::Game), reset_gravity);
+    }
+}
+
+/// Redirects gravity for dynamic bodies inside it.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+#[require(LevelObject, Transform, Sensor, CollidingEntities)]
+pub struct GravityZone {
+    pub gravity: Vec3,
+}
+
+/// The direction opposite to the gravity a body currently feels.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+pub struct GravityUp(pub Dir3);
+
+impl Default for GravityUp {
+    fn default() -> Self {
+        Self(Dir3::Y)
+    }
+}
+
+impl GravityUp {
+    /// The up of a body feeling `gravity`, `None` without any gravity.
+    pub fn from_gravity(gravity: Vec3) -> Option<Self> {
+        Dir3::new(-gravity).ok().map(Self)
+    }
+}
+
+/// Rotation from a frame where up is [`Vec3::Y`] to a frame where up is `up`.
+pub fn up_frame(up: Dir3) -> Quat {
+    Quat::from_rotation_arc(Vec3::Y, *up)
+}
+
+fn apply_gravity_zones(
+    zones: Query<(&GravityZone, &CollidingEntities)>,
+    colliders: Query<&ColliderOf>,
+    bodies: Query<(
+        Entity,
+        &RigidBody,
+        &mut LinearVelocity,
+        Option<&GravityScale>,
+        Option<&mut GravityUp>,
+    )>,
+    gravity: Res<Gravity>,
+    time: Res<Time>,
+) {
+    let mut zone_gravity = HashMap::new();
+    for (zone, colliding) in zones {
+        for entity in colliding_bodies(colliding, &colliders) {
+            zone_gravity.entry(entity).or_insert(zone.gravity);
+        }
+    }
+
+    for (entity, body, mut velocity, scale, up) in bodies {
+        if !body.is_dynamic() {
+            continue;
+        }
+
+        let felt = zone_gravity.get(&entity).copied().unwrap_or(gravity.0);
+        if felt != gravity.0 {
+            let scale = scale.map_or(1.0, |scale| scale.0);
+            velocity.0 += (felt - gravity.0) * scale * time.delta_secs();
+        }
+
+        // without any gravity, up stays where it was
+        if let Some(mut up) = up
+            && let Some(felt_up) = GravityUp::from_gravity(felt)
+        {
+            up.set_if_neq(felt_up);
+        }
+    }
+}
+
+fn reset_gravity(mut gravity: ResMut<Gravity>) {
+    *gravity = Gravity::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::entity::EntityHashSet;
+    use bevy::ecs::system::RunSystemOnce;
+    use std::time::Duration;
+
+    #[test]
+    fn test_zone_turns_model() {
+        let mut world = World::new();
+        world.insert_resource(Gravity(Vec3::NEG_Y * 10.0));
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs(1));
+        world.insert_resource(time);
+
+        // a dynamic model, with its colliders on two child entities
+        let model = world.spawn((RigidBody::Dynamic, GravityUp::default())).id();
+        let parts = [
+            world.spawn(ColliderOf { body: model }).id(),
+            world.spawn(ColliderOf { body: model }).id(),
+        ];
+        world.spawn((
+            GravityZone {
+                gravity: Vec3::Y * 10.0,
+            },
+            CollidingEntities(EntityHashSet::from_iter(parts)),
+        ));
+
+        world
+            .run_system_once(apply_gravity_zones)
+            .expect("failed to apply gravity zones");
+
+        let model = world.entity(model);
+        assert_eq!(model.get::<LinearVelocity>().unwrap().0, Vec3::Y * 20.0);
+        assert_eq!(model.get::<GravityUp>().unwrap().0, Dir3::NEG_Y);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::game::assets::BuiltinAssetsState;
+use crate::game::records::ParTimes;
 use bevy::asset::io::Reader;
 use bevy::asset::{AssetLoader, LoadContext};
 use bevy::prelude::*;
@@ -47,6 +48,24 @@ pub struct LevelIndex {
     pub levels: HashMap<String, LevelRef>,
 }
 
+impl LevelIndex {
+    /// Names of the levels that need to be completed to unlock the given level.
+    ///
+    /// Levels without a `requires` list are always unlocked.
+    pub fn requirements(&self, name: &str) -> Vec<&str> {
+        self.levels
+            .get(name)
+            .map(|level| level.requires.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// The level after the given level in the index, if any.
+    pub fn next_level(&self, name: &str) -> Option<&LevelRef> {
+        let pos = self.order.iter().position(|other| other == name)?;
+        self.order.get(pos + 1).map(|next| &self.levels[next])
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct LevelIndexJson {
     levels: Vec<LevelRef>,
@@ -57,6 +76,10 @@ pub struct LevelRef {
     pub name: String,
     pub display: String,
     pub path: String,
+    #[serde(default)]
+    pub requires: Vec<String>,
+    #[serde(flatten)]
+    pub times: ParTimes,
 }
 
 impl AssetLoader for LevelIndexLoader {
@@ -77,10 +100,28 @@ impl AssetLoader for LevelIndexLoader {
         let mut order = vec![];
         let mut levels = HashMap::new();
         for level in index.levels {
+            if let Some((name, time)) = level.times.invalid() {
+                return Err(LevelIndexLoadingError::InvalidTime {
+                    level: level.name,
+                    name,
+                    time,
+                });
+            }
             order.push(level.name.clone());
             levels.insert(level.name.clone(), level);
         }
 
+        for level in levels.values() {
+            for required in level.requires.iter() {
+                if !levels.contains_key(required) {
+                    warn!(
+                        "Level '{}' requires unknown level '{}'",
+                        &level.name, required
+                    );
+                }
+            }
+        }
+
         Ok(LevelIndex { order, levels })
     }
 }
@@ -91,4 +132,10 @@ pub enum LevelIndexLoadingError {
     Io(#[from] std::io::Error),
     #[error("JSON parse error {0}")]
     Json(#[from] serde_json::Error),
+    #[error("Level '{level}' has a {name} time of {time}, it must be a positive number of seconds")]
+    InvalidTime {
+        level: String,
+        name: &'static str,
+        time: f32,
+    },
 }
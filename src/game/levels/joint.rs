@@ -0,0 +1,83 @@
+//! Motors for jointed level objects. The joints themselves are avian's.
+
+use crate::game::game_state::GameState;
+use crate::game::levels::DynamicLevelObject;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+/// How quickly a motor brings its body up to speed.
+pub const MOTOR_GRIP: f32 = 8.0;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct JointPlugin;
+
+impl Plugin for JointPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            drive_joint_motors.run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Spins `body2` around a hinge relative to `body1`, like a motorized revolute joint.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[require(DynamicLevelObject)]
+pub struct JointMotor {
+    pub body1: Entity,
+    pub body2: Entity,
+    /// Hinge axis, relative to `body1`.
+    pub axis: Dir3,
+    /// Target angular speed around the axis, in radians per second.
+    pub speed: f32,
+    /// Most angular acceleration the motor can apply.
+    pub strength: f32,
+}
+
+impl JointMotor {
+    /// Change of angular velocity around `axis` to bring `relative_speed` closer to the target.
+    pub fn acceleration(&self, relative_speed: f32) -> f32 {
+        ((self.speed - relative_speed) * MOTOR_GRIP).clamp(-self.strength, self.strength)
+    }
+}
+
+fn drive_joint_motors(
+    motors: Query<&JointMotor>,
+    mut bodies: Query<(&Rotation, &mut AngularVelocity)>,
+    time: Res<Time>,
+) {
+    for motor in motors {
+        let Ok((rotation, velocity1)) = bodies.get(motor.body1) else {
+            continue;
+        };
+        let axis = rotation.0 * motor.axis;
+        let velocity1 = velocity1.0;
+
+        let Ok((_, mut velocity2)) = bodies.get_mut(motor.body2) else {
+            continue;
+        };
+        let relative_speed = (velocity2.0 - velocity1).dot(*axis);
+        velocity2.0 += axis * motor.acceleration(relative_speed) * time.delta_secs();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_motor_acceleration() {
+        let motor = JointMotor {
+            body1: Entity::PLACEHOLDER,
+            body2: Entity::PLACEHOLDER,
+            axis: Dir3::Y,
+            speed: 1.0,
+            strength: 4.0,
+        };
+
+        assert_eq!(motor.acceleration(1.0), 0.0);
+        assert_eq!(motor.acceleration(0.75), 2.0);
+        assert_eq!(motor.acceleration(-5.0), 4.0);
+    }
+}
@@ -0,0 +1,241 @@
+//! Keys the player carries around, and the doors they unlock.
+//!
+//! Locked doors are [`LevelButtonDoor`]s whose requirement is only met once they are unlocked, so
+//! they slide open and play the same sounds as any other door.
+
+use crate::game::game_state::GameState;
+use crate::game::levels::button::{ButtonRequirement, LevelButtonDoor};
+use crate::game::levels::checkpoint::ActiveCheckpoint;
+use crate::game::levels::pickup::{Pickup, PickupTouched};
+use crate::game::levels::{LevelObject, LevelRestartEvent};
+use crate::game::logic::Player;
+use crate::game::state::AppState;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+/// Size of a held key's icon on the HUD.
+pub const KEY_ICON_SIZE: f32 = 28.0;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct KeyPlugin;
+
+impl Plugin for KeyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(unlock_doors)
+            .add_observer(restore_inventory)
+            .add_observer(pick_up_key)
+            .add_systems(OnEnter(AppState::Game), spawn_key_hud)
+            .add_systems(
+                Update,
+                save_inventory
+                    .run_if(resource_changed::<ActiveCheckpoint>)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (show_held_keys, update_key_hud).run_if(in_state(AppState::Game)),
+            );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub struct Key {
+    pub id: String,
+    pub color: Color,
+}
+
+/// Keys held by the player.
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+pub struct Inventory {
+    pub keys: Vec<Key>,
+    /// Keys held when the active checkpoint was reached.
+    pub checkpoint_keys: Vec<Key>,
+}
+
+impl Inventory {
+    pub fn has(&self, id: &str) -> bool {
+        self.keys.iter().any(|key| key.id == id)
+    }
+
+    pub fn pick_up(&mut self, key: &Key) {
+        if !self.has(&key.id) {
+            self.keys.push(key.clone());
+        }
+    }
+
+    /// Goes back to the keys held at the checkpoint, or to no keys at all.
+    pub fn restore(&mut self, at_checkpoint: bool) {
+        if at_checkpoint {
+            self.keys = self.checkpoint_keys.clone();
+        } else {
+            *self = Inventory::default();
+        }
+    }
+}
+
+/// A key lying in the level. It's hidden while the player holds its key, so it comes back when
+/// the inventory is reset.
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[require(LevelObject, Pickup)]
+pub struct KeyPickup {
+    pub key: Key,
+}
+
+/// Opens its [`LevelButtonDoor`] when the player touches it holding the key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Hash, Component)]
+#[require(CollisionEventsEnabled)]
+pub struct LockedDoor {
+    pub key: String,
+}
+
+impl LockedDoor {
+    /// Requirement that keeps the door shut, no input of an empty `any` is met.
+    pub fn locked() -> ButtonRequirement {
+        ButtonRequirement::Any(vec![])
+    }
+
+    /// Requirement that keeps the door open, every input of an empty `all` is met.
+    pub fn unlocked() -> ButtonRequirement {
+        ButtonRequirement::All(vec![])
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+struct KeyHud;
+
+fn pick_up_key(
+    touched: On<PickupTouched>,
+    pickups: Query<&KeyPickup>,
+    mut players: Query<&mut Inventory, With<Player>>,
+) {
+    if let Ok(pickup) = pickups.get(touched.entity)
+        && let Ok(mut inventory) = players.get_mut(touched.player)
+    {
+        info!("Key '{}' picked up", pickup.key.id);
+        inventory.pick_up(&pickup.key);
+    }
+}
+
+// a key is taken for as long as the player holds it, so it comes back when the inventory is reset
+fn show_held_keys(
+    pickups: Query<(&KeyPickup, &mut Pickup)>,
+    players: Query<&Inventory, With<Player>>,
+) {
+    for (key, mut pickup) in pickups {
+        let taken = players.iter().any(|inventory| inventory.has(&key.key.id));
+        pickup.set_if_neq(Pickup { taken });
+    }
+}
+
+fn unlock_doors(
+    collision: On<CollisionStart>,
+    mut cmd: Commands,
+    mut doors: Query<(&LockedDoor, &mut LevelButtonDoor)>,
+    players: Query<&Inventory, With<Player>>,
+) {
+    let Ok((locked, mut door)) = doors.get_mut(collision.collider1) else {
+        return;
+    };
+
+    if let Ok(inventory) = players.get(collision.collider2)
+        && inventory.has(&locked.key)
+    {
+        info!("Door unlocked with key '{}'", locked.key);
+        door.requires = LockedDoor::unlocked();
+        cmd.entity(collision.collider1).remove::<LockedDoor>();
+    }
+}
+
+fn save_inventory(players: Query<&mut Inventory, With<Player>>, checkpoint: Res<ActiveCheckpoint>) {
+    if checkpoint.is_active() {
+        for mut inventory in players {
+            inventory.checkpoint_keys = inventory.keys.clone();
+        }
+    }
+}
+
+fn restore_inventory(
+    _on: On<LevelRestartEvent>,
+    players: Query<&mut Inventory, With<Player>>,
+    checkpoint: Res<ActiveCheckpoint>,
+) {
+    for mut inventory in players {
+        inventory.restore(checkpoint.is_active());
+    }
+}
+
+fn spawn_key_hud(mut cmd: Commands) {
+    cmd.spawn((
+        KeyHud,
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(20),
+            right: px(20),
+            column_gap: px(10),
+            ..default()
+        },
+        DespawnOnExit(AppState::Game),
+    ));
+}
+
+fn update_key_hud(
+    mut cmd: Commands,
+    huds: Query<Entity, With<KeyHud>>,
+    players: Query<&Inventory, (With<Player>, Changed<Inventory>)>,
+) {
+    for inventory in players {
+        for hud in huds {
+            cmd.entity(hud)
+                .despawn_related::<Children>()
+                .with_children(|hud| {
+                    for key in inventory.keys.iter() {
+                        hud.spawn((
+                            Node {
+                                width: px(KEY_ICON_SIZE),
+                                height: px(KEY_ICON_SIZE),
+                                ..default()
+                            },
+                            BackgroundColor(key.color),
+                            BorderRadius::all(px(KEY_ICON_SIZE / 2.0)),
+                        ));
+                    }
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inventory_restore() {
+        let red = Key {
+            id: "red".to_string(),
+            color: Color::WHITE,
+        };
+        let blue = Key {
+            id: "blue".to_string(),
+            ..red.clone()
+        };
+
+        let mut inventory = Inventory::default();
+        inventory.pick_up(&red);
+        inventory.pick_up(&red);
+        assert_eq!(inventory.keys.len(), 1);
+
+        inventory.checkpoint_keys = inventory.keys.clone();
+        inventory.pick_up(&blue);
+        inventory.restore(true);
+        assert!(inventory.has("red"));
+        assert!(!inventory.has("blue"));
+
+        inventory.restore(false);
+        assert!(!inventory.has("red"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Cannons and launch tubes that catch the player and fire them along an arc onto a target.
+//!
+//! The launch velocity accounts for the player's [`LinearDamping`] and [`GravityScale`], and levels
+//! can't fire through gravity zones, so the ball lands on the target as long as nothing is in the
+//! way.
+
+use crate::game::camera::{CameraUp, PlayerCamera};
+use crate::game::game_state::GameState;
+use crate::game::levels::gravity::up_frame;
+use crate::game::levels::{LevelObject, LevelRestartEvent};
+use crate::game::logic::Player;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use std::f32::consts::{PI, TAU};
+
+/// How quickly the camera turns towards the target while framing a shot.
+pub const FRAME_SPEED: f32 = 4.0;
+
+const ARC_SEGMENTS: u32 = 32;
+const ARC_COLOR: Color = Color::srgb(1.0, 0.5, 0.1);
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct LauncherPlugin;
+
+impl Plugin for LauncherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<LauncherGizmos>()
+            .add_observer(capture_player)
+            .add_observer(release_on_restart)
+            .add_systems(
+                FixedUpdate,
+                run_launchers.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    frame_launches.run_if(in_state(GameState::Playing)),
+                    draw_launch_arcs,
+                ),
+            );
+    }
+}
+
+/// Gizmos showing the predicted flight of launchers, toggled together with the physics gizmos.
+#[derive(
+    Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, GizmoConfigGroup, Reflect,
+)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+pub struct LauncherGizmos;
+
+/// Catches the player, then fires them at `target` after `delay` seconds.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[require(LevelObject, Transform, Sensor, CollisionEventsEnabled)]
+pub struct Launcher {
+    pub target: Vec3,
+    pub delay: f32,
+    /// Seconds the player spends in the air, a longer flight makes a higher arc.
+    pub flight_time: f32,
+    /// Whether to turn the camera towards the target while the player waits.
+    pub frame_camera: bool,
+}
+
+/// Marks a player held by a launcher.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[component(storage = "SparseSet")]
+pub struct Launching {
+    pub launcher: Entity,
+    /// Seconds left before the player is fired.
+    pub remaining: f32,
+}
+
+impl Launcher {
+    /// Velocity to leave `from` with to land on the target.
+    pub fn velocity(&self, from: Vec3, gravity: Vec3, damping: f32) -> Vec3 {
+        let time = self.flight_time;
+        if damping <= 0.0 {
+            return (self.target - from - 0.5 * gravity * time * time) / time;
+        }
+
+        let decay = (1.0 - (-damping * time).exp()) / damping;
+        (self.target - from - gravity * time / damping) / decay + gravity / damping
+    }
+
+    /// Points along the flight from `from` to the target.
+    pub fn arc(&self, from: Vec3, gravity: Vec3, damping: f32) -> impl Iterator<Item = Vec3> {
+        let velocity = self.velocity(from, gravity, damping);
+        let flight_time = self.flight_time;
+        (0..=ARC_SEGMENTS).map(move |segment| {
+            let time = flight_time * segment as f32 / ARC_SEGMENTS as f32;
+            flight_position(from, velocity, gravity, damping, time)
+        })
+    }
+}
+
+/// Where a body that left `from` at `velocity` is after `time` seconds.
+pub fn flight_position(from: Vec3, velocity: Vec3, gravity: Vec3, damping: f32, time: f32) -> Vec3 {
+    if damping <= 0.0 {
+        return from + velocity * time + 0.5 * gravity * time * time;
+    }
+
+    let decay = (1.0 - (-damping * time).exp()) / damping;
+    from + (velocity - gravity / damping) * decay + gravity * time / damping
+}
+
+fn capture_player(
+    collision: On<CollisionStart>,
+    mut cmd: Commands,
+    launchers: Query<&Launcher>,
+    players: Query<(), (With<Player>, Without<Launching>)>,
+) {
+    if let Ok(launcher) = launchers.get(collision.collider1)
+        && players.contains(collision.collider2)
+    {
+        cmd.entity(collision.collider2).insert(Launching {
+            launcher: collision.collider1,
+            remaining: launcher.delay,
+        });
+    }
+}
+
+fn release_on_restart(
+    _on: On<LevelRestartEvent>,
+    mut cmd: Commands,
+    players: Query<Entity, With<Launching>>,
+) {
+    for player in players {
+        cmd.entity(player).remove::<Launching>();
+    }
+}
+
+fn run_launchers(
+    mut cmd: Commands,
+    launchers: Query<(&Launcher, &GlobalTransform)>,
+    players: Query<(
+        Entity,
+        &mut Launching,
+        &mut Position,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+        Option<&LinearDamping>,
+        Option<&GravityScale>,
+    )>,
+    gravity: Res<Gravity>,
+    time: Res<Time>,
+) {
+    for (entity, mut launching, mut position, mut linear, mut angular, damping, scale) in players {
+        let Ok((launcher, transform)) = launchers.get(launching.launcher) else {
+            cmd.entity(entity).remove::<Launching>();
+            continue;
+        };
+
+        position.0 = transform.translation();
+        angular.0 = Vec3::ZERO;
+        launching.remaining -= time.delta_secs();
+        if launching.remaining > 0.0 {
+            linear.0 = Vec3::ZERO;
+        } else {
+            let damping = damping.map_or(0.0, |damping| damping.0);
+            let gravity = gravity.0 * scale.map_or(1.0, |scale| scale.0);
+            linear.0 = launcher.velocity(position.0, gravity, damping);
+            cmd.entity(entity).remove::<Launching>();
+        }
+    }
+}
+
+fn frame_launches(
+    mut cameras: Query<(&mut PlayerCamera, &CameraUp)>,
+    launchers: Query<(&Launcher, &GlobalTransform)>,
+    players: Query<&Launching, With<Player>>,
+    time: Res<Time>,
+) {
+    for launching in players {
+        let Ok((launcher, transform)) = launchers.get(launching.launcher) else {
+            continue;
+        };
+        if !launcher.frame_camera {
+            continue;
+        }
+        let towards = launcher.target - transform.translation();
+
+        for (mut camera, camera_up) in cameras.iter_mut() {
+            // the yaw is measured around the camera's up, so the target is turned into its frame
+            let towards = up_frame(camera_up.0).inverse() * towards;
+            if towards.xz().length_squared() < 0.001 {
+                continue;
+            }
+
+            // the camera looks along -(sin yaw, 0, cos yaw)
+            let yaw = f32::atan2(-towards.x, -towards.z);
+            let turn = (yaw - camera.yaw + PI).rem_euclid(TAU) - PI;
+            camera.yaw += turn * (FRAME_SPEED * time.delta_secs()).min(1.0);
+        }
+    }
+}
+
+fn draw_launch_arcs(
+    mut gizmos: Gizmos<LauncherGizmos>,
+    launchers: Query<(&Launcher, &GlobalTransform)>,
+    players: Query<(Option<&LinearDamping>, Option<&GravityScale>), With<Player>>,
+    gravity: Res<Gravity>,
+) {
+    let (damping, scale) = players.iter().next().unwrap_or_default();
+    let damping = damping.map_or(0.0, |damping| damping.0);
+    let gravity = gravity.0 * scale.map_or(1.0, |scale| scale.0);
+    for (launcher, transform) in launchers {
+        gizmos.linestrip(
+            launcher.arc(transform.translation(), gravity, damping),
+            ARC_COLOR,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_lands_on_target() {
+        let from = Vec3::new(0.0, 1.0, 0.0);
+        let launcher = Launcher {
+            target: Vec3::new(4.0, 3.0, -20.0),
+            delay: 0.0,
+            flight_time: 2.0,
+            frame_camera: false,
+        };
+        let gravity = Vec3::NEG_Y * 9.81;
+
+        for damping in [0.0, 0.25] {
+            let velocity = launcher.velocity(from, gravity, damping);
+            let landing = flight_position(from, velocity, gravity, damping, launcher.flight_time);
+            assert!(landing.distance(launcher.target) < 1e-3);
+
+            let arc = launcher.arc(from, gravity, damping).collect::<Vec<_>>();
+            assert!(arc[0].distance(from) < 1e-3);
+            assert!(arc[arc.len() - 1].distance(launcher.target) < 1e-3);
+        }
+    }
+}
@@ -1,16 +1,37 @@
 pub mod button;
+pub mod checkpoint;
+pub mod crumble;
 pub mod death;
 pub mod finish_point;
+pub mod force;
+pub mod gem;
+pub mod gravity;
 pub mod index;
+pub mod joint;
+pub mod key;
+pub mod launcher;
+pub mod model;
+pub mod mover;
+pub mod pad;
+pub mod pickup;
+pub mod powerup;
 pub mod serial;
+pub mod signal;
+pub mod spawner;
+pub mod surface;
+pub mod teleporter;
 
 use crate::game::assets::fonts::FontNames;
 use crate::game::assets::preload::Preloads;
+use crate::game::levels::checkpoint::ActiveCheckpoint;
 use crate::game::levels::index::{LevelIndex, LevelIndexLoader, on_level_index_loaded};
 use crate::game::levels::serial::SerialLevelLoader;
 use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::surface::Surface;
 use crate::game::state::AppState;
+use avian3d::prelude::{ColliderOf, CollidingEntities};
 use bevy::asset::AssetLoadFailedEvent;
+use bevy::ecs::query::QueryFilter;
 use bevy::ecs::system::entity_command;
 use bevy::prelude::*;
 use serial::level::SerialLevel;
@@ -36,7 +57,9 @@ impl Plugin for LevelsPlugin {
                 level_loading_error.run_if(in_state(AppState::LoadingLevel)),
             )
             .add_observer(respawn_level)
-            .add_systems(OnExit(AppState::Game), (unselect_level, despawn_level));
+            .add_systems(OnExit(AppState::Game), despawn_level)
+            // the selected level is kept when going straight to the next level
+            .add_systems(OnEnter(AppState::MainMenu), unselect_level);
     }
 }
 
@@ -60,6 +83,12 @@ pub struct LevelHandle(pub Handle<SerialLevel>);
 #[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
 pub struct LevelObject;
 
+/// Level object that keeps its state when the level is restarted from a checkpoint.
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+#[require(LevelObject)]
+pub struct DynamicLevelObject;
+
 #[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
 #[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
 pub struct PlayerSpawnPoint;
@@ -102,7 +131,7 @@ fn despawn_level(mut cmd: Commands, query: Query<Entity, With<LevelObject>>) {
     despawn_level_impl(&mut cmd, query);
 }
 
-fn despawn_level_impl(cmd: &mut Commands, query: Query<Entity, With<LevelObject>>) {
+fn despawn_level_impl<F: QueryFilter>(cmd: &mut Commands, query: Query<Entity, F>) {
     for level in query {
         // ignore if we try to despawn something twice
         cmd.entity(level).queue_silenced(entity_command::despawn());
@@ -113,14 +142,29 @@ fn respawn_level(
     _on: On<LevelRestartEvent>,
     mut cmd: Commands,
     query: Query<Entity, With<LevelObject>>,
+    static_query: Query<
+        Entity,
+        (
+            With<LevelObject>,
+            Without<DynamicLevelObject>,
+            Without<ChildOf>,
+        ),
+    >,
+    checkpoint: Res<ActiveCheckpoint>,
     asset_server: Res<AssetServer>,
     preloads: Res<Preloads>,
     fonts: Res<FontNames>,
     level_handle: Res<LevelHandle>,
     level_assets: Res<Assets<SerialLevel>>,
+    surfaces: Res<Assets<Surface>>,
 ) {
-    // TODO: Implement checkpoint system
-    despawn_level_impl(&mut cmd, query);
+    // restarting from a checkpoint keeps dynamic objects where they are
+    let dyn_assets = !checkpoint.is_active();
+    if dyn_assets {
+        despawn_level_impl(&mut cmd, query);
+    } else {
+        despawn_level_impl(&mut cmd, static_query);
+    }
     spawn_level_impl(
         &mut cmd,
         &asset_server,
@@ -128,7 +172,8 @@ fn respawn_level(
         &fonts,
         &level_handle,
         &level_assets,
-        true,
+        &surfaces,
+        dyn_assets,
     );
 }
 
@@ -141,15 +186,18 @@ fn spawn_level(
     fonts: Res<FontNames>,
     level_handle: Option<Res<LevelHandle>>,
     level_assets: Res<Assets<SerialLevel>>,
+    surfaces: Res<Assets<Surface>>,
     app_state: Res<State<AppState>>,
     mut next_state: ResMut<NextState<AppState>>,
     level_lock: ResMut<LevelLoadingLock>,
+    mut checkpoint: ResMut<ActiveCheckpoint>,
 ) {
     if let Some(level_handle) = level_handle
         && *level_lock == LevelLoadingLock::NotCanceled
     {
         for e in msg.read() {
             if e.is_loaded_with_dependencies(&level_handle.0) {
+                checkpoint.0 = None;
                 despawn_level_impl(&mut cmd, query);
                 spawn_level_impl(
                     &mut cmd,
@@ -158,6 +206,7 @@ fn spawn_level(
                     &fonts,
                     &level_handle,
                     &level_assets,
+                    &surfaces,
                     true,
                 );
 
@@ -180,6 +229,7 @@ fn spawn_level_impl(
     fonts: &FontNames,
     level_handle: &LevelHandle,
     level_assets: &Assets<SerialLevel>,
+    surfaces: &Assets<Surface>,
     dyn_assets: bool,
 ) {
     let level = level_assets
@@ -191,9 +241,29 @@ fn spawn_level_impl(
         assets,
         preloads,
         fonts,
+        surfaces,
     });
 }
 
+/// The bodies of the colliders in `colliding`, each listed once. Models keep their colliders on
+/// child entities, so colliders are resolved to their body through [`ColliderOf`].
+pub fn colliding_bodies(
+    colliding: &CollidingEntities,
+    colliders: &Query<&ColliderOf>,
+) -> Vec<Entity> {
+    let mut bodies = colliding
+        .iter()
+        .map(|entity| {
+            colliders
+                .get(*entity)
+                .map_or(*entity, |collider| collider.body)
+        })
+        .collect::<Vec<_>>();
+    bodies.sort_unstable();
+    bodies.dedup();
+    bodies
+}
+
 fn level_loading_error(
     mut msg: MessageReader<AssetLoadFailedEvent<SerialLevel>>,
     handle: Option<Res<LevelHandle>>,
@@ -0,0 +1,86 @@
+//! Level geometry loaded from glTF scenes, with colliders generated from their meshes.
+
+use crate::game::levels::LevelObject;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+/// How colliders are made from a model's meshes.
+#[derive(
+    Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Reflect, strum::VariantArray, strum::Display,
+)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+#[strum(serialize_all = "snake_case")]
+pub enum ModelCollider {
+    /// Exact, but hollow, so only good for static and kinematic bodies.
+    #[default]
+    Trimesh,
+    ConvexHull,
+    ConvexDecomposition,
+    None,
+}
+
+impl ModelCollider {
+    pub fn collider_for(self, mesh: &Mesh) -> Option<Collider> {
+        match self {
+            ModelCollider::Trimesh => Collider::trimesh_from_mesh(mesh),
+            ModelCollider::ConvexHull => Collider::convex_hull_from_mesh(mesh),
+            ModelCollider::ConvexDecomposition => Collider::convex_decomposition_from_mesh(mesh),
+            ModelCollider::None => None,
+        }
+    }
+}
+
+#[derive(
+    Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Reflect, strum::VariantArray, strum::Display,
+)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+#[strum(serialize_all = "snake_case")]
+pub enum ModelBody {
+    #[default]
+    Static,
+    Kinematic,
+    Dynamic,
+}
+
+impl ModelBody {
+    pub fn rigid_body(self) -> RigidBody {
+        match self {
+            ModelBody::Static => RigidBody::Static,
+            ModelBody::Kinematic => RigidBody::Kinematic,
+            ModelBody::Dynamic => RigidBody::Dynamic,
+        }
+    }
+}
+
+/// A glTF scene in the level. Colliders are added to its meshes once the scene is spawned, see
+/// [`add_model_colliders`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+#[require(LevelObject, Transform, Visibility)]
+pub struct LevelModel {
+    pub collider: ModelCollider,
+}
+
+/// Observer for [`LevelModel`] scenes. The colliders go on the mesh entities and belong to the
+/// model's rigid body.
+pub fn add_model_colliders(
+    scene: On<SceneInstanceReady>,
+    mut cmd: Commands,
+    models: Query<&LevelModel>,
+    children: Query<&Children>,
+    mesh_handles: Query<&Mesh3d>,
+    meshes: Res<Assets<Mesh>>,
+) {
+    let Ok(model) = models.get(scene.entity) else {
+        return;
+    };
+
+    for child in children.iter_descendants(scene.entity) {
+        if let Ok(mesh3d) = mesh_handles.get(child)
+            && let Some(mesh) = meshes.get(&mesh3d.0)
+            && let Some(collider) = model.collider.collider_for(mesh)
+        {
+            cmd.entity(child).insert(collider);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Kinematic platforms that follow a path of waypoints.
+//!
+//! Movers are driven by velocity rather than by setting their transform, so that anything resting
+//! on them is carried along by the physics engine.
+
+use crate::game::game_state::GameState;
+use crate::game::levels::DynamicLevelObject;
+use crate::game::levels::signal::Signals;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct MoverPlugin;
+
+impl Plugin for MoverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            drive_movers.run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+#[derive(
+    Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Reflect, strum::VariantArray, strum::Display,
+)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+#[strum(serialize_all = "snake_case")]
+pub enum MoverMode {
+    /// Goes back and forth along the path.
+    #[default]
+    PingPong,
+    /// Goes from the last waypoint back to the first one and starts over.
+    Loop,
+    /// Stops at the last waypoint.
+    Once,
+}
+
+#[derive(
+    Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Reflect, strum::VariantArray, strum::Display,
+)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+#[strum(serialize_all = "snake_case")]
+pub enum MoverEase {
+    #[default]
+    Linear,
+    In,
+    Out,
+    InOut,
+    Smooth,
+}
+
+impl MoverEase {
+    pub fn function(self) -> EaseFunction {
+        match self {
+            MoverEase::Linear => EaseFunction::Linear,
+            MoverEase::In => EaseFunction::QuadraticIn,
+            MoverEase::Out => EaseFunction::QuadraticOut,
+            MoverEase::InOut => EaseFunction::QuadraticInOut,
+            MoverEase::Smooth => EaseFunction::SmoothStep,
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub struct MoverWaypoint {
+    pub trans: Transform,
+    /// Seconds it takes to get to this waypoint from the one before it. For the first waypoint,
+    /// this is only used by looping movers to get back to the start.
+    pub duration: f32,
+    pub ease: MoverEase,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+#[require(DynamicLevelObject, Transform, RigidBody::Kinematic)]
+pub struct LevelMover {
+    pub waypoints: Vec<MoverWaypoint>,
+    pub mode: MoverMode,
+    /// Signal that starts the mover, it starts right away without one.
+    pub start: Option<String>,
+    pub started: bool,
+    pub elapsed: f32,
+}
+
+impl LevelMover {
+    /// The waypoints each segment of the path goes between.
+    fn segments(&self) -> impl Iterator<Item = (&MoverWaypoint, &MoverWaypoint)> {
+        let count = match self.mode {
+            MoverMode::Loop => self.waypoints.len(),
+            MoverMode::PingPong | MoverMode::Once => self.waypoints.len().saturating_sub(1),
+        };
+        (0..count).map(|i| {
+            (
+                &self.waypoints[i],
+                &self.waypoints[(i + 1) % self.waypoints.len()],
+            )
+        })
+    }
+
+    fn path_duration(&self) -> f32 {
+        self.segments().map(|(_, to)| to.duration).sum()
+    }
+
+    /// Where the mover should be after moving for `elapsed` seconds.
+    pub fn transform_at(&self, elapsed: f32) -> Transform {
+        let duration = self.path_duration();
+        let mut time = match self.mode {
+            MoverMode::PingPong => {
+                let time = elapsed.rem_euclid(duration * 2.0);
+                if time > duration {
+                    duration * 2.0 - time
+                } else {
+                    time
+                }
+            }
+            MoverMode::Loop => elapsed.rem_euclid(duration),
+            MoverMode::Once => elapsed.min(duration),
+        };
+
+        for (from, to) in self.segments() {
+            if time <= to.duration {
+                let progress = to.ease.function().sample_clamped(time / to.duration);
+                return Transform::interpolate(&from.trans, &to.trans, progress);
+            }
+            time -= to.duration;
+        }
+
+        // only reachable through rounding errors at the very end of the path
+        self.segments()
+            .last()
+            .map_or_else(Transform::default, |(_, to)| to.trans)
+    }
+}
+
+fn drive_movers(
+    movers: Query<(
+        &mut LevelMover,
+        &Position,
+        &Rotation,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+    )>,
+    signals: Res<Signals>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+    if delta <= 0.0 {
+        return;
+    }
+
+    for (mut mover, position, rotation, mut linear, mut angular) in movers {
+        if !mover.started {
+            if mover
+                .start
+                .as_ref()
+                .is_some_and(|start| !signals.is_on(start))
+            {
+                continue;
+            }
+            mover.started = true;
+        }
+
+        mover.elapsed += delta;
+        let target = mover.transform_at(mover.elapsed);
+
+        // aim to be exactly at the target after this physics step
+        linear.0 = (target.translation - position.0) / delta;
+
+        let mut rotation_delta = target.rotation * rotation.0.inverse();
+        if rotation_delta.w < 0.0 {
+            rotation_delta = -rotation_delta;
+        }
+        let (axis, angle) = rotation_delta.to_axis_angle();
+        angular.0 = axis * angle / delta;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mover(mode: MoverMode) -> LevelMover {
+        LevelMover {
+            waypoints: [Vec3::ZERO, Vec3::X, Vec3::new(1.0, 0.0, 1.0)]
+                .map(|pos| MoverWaypoint {
+                    trans: Transform::from_translation(pos),
+                    duration: 1.0,
+                    ease: MoverEase::Linear,
+                })
+                .to_vec(),
+            mode,
+            start: None,
+            started: false,
+            elapsed: 0.0,
+        }
+    }
+
+    fn position_at(mover: &LevelMover, elapsed: f32) -> Vec3 {
+        mover.transform_at(elapsed).translation
+    }
+
+    #[test]
+    fn test_mover_ping_pong() {
+        let mover = mover(MoverMode::PingPong);
+        assert!(position_at(&mover, 0.5).abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-5));
+        assert!(position_at(&mover, 2.0).abs_diff_eq(Vec3::new(1.0, 0.0, 1.0), 1e-5));
+        assert!(position_at(&mover, 2.5).abs_diff_eq(Vec3::new(1.0, 0.0, 0.5), 1e-5));
+        assert!(position_at(&mover, 4.0).abs_diff_eq(Vec3::ZERO, 1e-5));
+    }
+
+    #[test]
+    fn test_mover_loop() {
+        let mover = mover(MoverMode::Loop);
+        assert!(position_at(&mover, 2.5).abs_diff_eq(Vec3::new(0.5, 0.0, 0.5), 1e-5));
+        assert!(position_at(&mover, 3.5).abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-5));
+    }
+
+    #[test]
+    fn test_mover_once() {
+        let mover = mover(MoverMode::Once);
+        assert!(position_at(&mover, 10.0).abs_diff_eq(Vec3::new(1.0, 0.0, 1.0), 1e-5));
+    }
+}
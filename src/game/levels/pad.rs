@@ -0,0 +1,114 @@
+//! Boost and bounce pads that launch anything rolling over them.
+
+use crate::game::game_state::GameState;
+use crate::game::levels::button::ButtonPresser;
+use crate::game::levels::gravity::GravityUp;
+use crate::game::levels::{LevelObject, colliding_bodies};
+use crate::game::logic::Player;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+/// Width and length of a pad.
+pub const PAD_SIZE: f32 = 1.0;
+/// How far above a pad things get launched.
+pub const PAD_REACH: f32 = 0.5;
+
+const PLAYBACK_SETTINGS: PlaybackSettings = PlaybackSettings::REMOVE.with_spatial(true);
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct PadPlugin;
+
+impl Plugin for PadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            trigger_pads.run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[require(LevelObject, Transform, Sensor, CollidingEntities)]
+pub struct LevelPad {
+    pub kind: PadKind,
+    /// Seconds after launching something before the pad can launch again.
+    pub cooldown: f32,
+    pub remaining: f32,
+    /// Played every time the pad launches something.
+    pub sound: Option<Handle<AudioSource>>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum PadKind {
+    /// Adds this to the velocity, in world space.
+    Boost(Vec3),
+    /// Sets the velocity along the body's up.
+    Bounce(f32),
+}
+
+impl PadKind {
+    pub fn launch(self, velocity: &mut Vec3, up: Dir3) {
+        match self {
+            PadKind::Boost(boost) => *velocity += boost,
+            PadKind::Bounce(speed) => {
+                *velocity = velocity.reject_from_normalized(*up) + *up * speed;
+            }
+        }
+    }
+}
+
+fn trigger_pads(
+    mut cmd: Commands,
+    pads: Query<(Entity, &mut LevelPad, &CollidingEntities)>,
+    colliders: Query<&ColliderOf>,
+    mut launchable: Query<
+        (&mut LinearVelocity, &GravityUp),
+        Or<(With<ButtonPresser>, With<Player>)>,
+    >,
+    time: Res<Time>,
+) {
+    for (entity, mut pad, colliding) in pads {
+        pad.remaining = (pad.remaining - time.delta_secs()).max(0.0);
+        if pad.remaining > 0.0 {
+            continue;
+        }
+
+        let mut launched = false;
+        for other in colliding_bodies(colliding, &colliders) {
+            if let Ok((mut velocity, up)) = launchable.get_mut(other) {
+                pad.kind.launch(&mut velocity.0, up.0);
+                launched = true;
+            }
+        }
+
+        if launched {
+            pad.remaining = pad.cooldown;
+
+            if let Some(sound) = &pad.sound {
+                cmd.entity(entity)
+                    .remove::<(AudioPlayer, SpatialAudioSink, PlaybackSettings)>()
+                    .insert((AudioPlayer::new(sound.clone()), PLAYBACK_SETTINGS));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_launch() {
+        let mut velocity = Vec3::new(1.0, -2.0, 0.0);
+        PadKind::Boost(Vec3::new(0.0, 0.0, 5.0)).launch(&mut velocity, Dir3::Y);
+        assert_eq!(velocity, Vec3::new(1.0, -2.0, 5.0));
+
+        PadKind::Bounce(6.0).launch(&mut velocity, Dir3::Y);
+        assert_eq!(velocity, Vec3::new(1.0, 6.0, 5.0));
+
+        PadKind::Bounce(4.0).launch(&mut velocity, Dir3::NEG_X);
+        assert_eq!(velocity, Vec3::new(-4.0, 6.0, 5.0));
+    }
+}
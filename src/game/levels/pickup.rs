@@ -0,0 +1,80 @@
+//! Small spinning items the player picks up by rolling into them.
+//!
+//! Gems, keys, power-ups and signal collectibles all require a [`Pickup`], and observe
+//! [`PickupTouched`] to decide what picking them up does.
+
+use crate::game::game_state::GameState;
+use crate::game::logic::Player;
+use crate::game::state::AppState;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            touch_pickups.run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            (spin_pickups, show_pickups).run_if(in_state(AppState::Game)),
+        );
+    }
+}
+
+/// Hidden while taken.
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+#[require(
+    Transform,
+    Visibility,
+    Sensor,
+    Collider::sphere(0.3),
+    CollidingEntities
+)]
+pub struct Pickup {
+    pub taken: bool,
+}
+
+/// Triggered every physics tick the player touches a [`Pickup`] that isn't taken.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, EntityEvent, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Hash)]
+pub struct PickupTouched {
+    pub entity: Entity,
+    pub player: Entity,
+}
+
+pub fn touch_pickups(
+    mut cmd: Commands,
+    pickups: Query<(Entity, &Pickup, &CollidingEntities)>,
+    players: Query<Entity, With<Player>>,
+) {
+    for (entity, pickup, colliding) in pickups {
+        if pickup.taken {
+            continue;
+        }
+
+        for player in players.iter_many(colliding.iter()) {
+            cmd.trigger(PickupTouched { entity, player });
+        }
+    }
+}
+
+fn spin_pickups(query: Query<&mut Transform, With<Pickup>>, time: Res<Time>) {
+    for mut trans in query {
+        trans.rotate_y(time.delta_secs());
+    }
+}
+
+fn show_pickups(query: Query<(&Pickup, &mut Visibility), Changed<Pickup>>) {
+    for (pickup, mut visibility) in query {
+        visibility.set_if_neq(if pickup.taken {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+    }
+}
@@ -0,0 +1,243 @@
+//! Timed power-ups. The player holds at most one, and uses it with
+//! [`PlayerInput::Activate`](crate::game::input::PlayerInput::Activate).
+
+use crate::game::game_state::GameState;
+use crate::game::levels::pickup::{Pickup, PickupTouched, touch_pickups};
+use crate::game::levels::surface::combine_rule;
+use crate::game::levels::{LevelObject, LevelRestartEvent};
+use crate::game::logic::{Player, TickInput};
+use avian3d::prelude::*;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+
+/// How long power-ups last when the level doesn't say.
+pub const DEFAULT_POWER_UP_DURATION: f32 = 5.0;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(reset_power_ups)
+            .add_observer(pick_up_power_up)
+            .add_systems(
+                FixedUpdate,
+                (activate_power_up, tick_power_up, apply_power_up_physics)
+                    .chain()
+                    .after(touch_pickups)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Reflect, strum::VariantArray, strum::Display)]
+#[reflect(Debug, Clone, PartialEq, Hash)]
+#[strum(serialize_all = "snake_case")]
+pub enum PowerUpKind {
+    SuperJump,
+    SuperSpeed,
+    /// Stops the player from bouncing.
+    ShockAbsorber,
+    SlowFall,
+}
+
+impl PowerUpKind {
+    /// The power-up with this kind's default effects.
+    pub fn power_up(self) -> PowerUp {
+        let power_up = PowerUp {
+            kind: self,
+            duration: DEFAULT_POWER_UP_DURATION,
+            jump: 1.0,
+            acceleration: 1.0,
+            bounce: None,
+            gravity: 1.0,
+        };
+        match self {
+            PowerUpKind::SuperJump => PowerUp {
+                jump: 2.0,
+                ..power_up
+            },
+            PowerUpKind::SuperSpeed => PowerUp {
+                acceleration: 2.5,
+                ..power_up
+            },
+            PowerUpKind::ShockAbsorber => PowerUp {
+                bounce: Some(0.0),
+                ..power_up
+            },
+            PowerUpKind::SlowFall => PowerUp {
+                gravity: 0.25,
+                ..power_up
+            },
+        }
+    }
+}
+
+/// What a power-up does while it's active.
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    /// Seconds the power-up lasts once activated.
+    pub duration: f32,
+    /// Multiplies the jump velocity.
+    pub jump: f32,
+    /// Multiplies the rolling acceleration.
+    pub acceleration: f32,
+    /// Replaces the player's bounciness.
+    pub bounce: Option<f32>,
+    /// Multiplies gravity.
+    pub gravity: f32,
+}
+
+/// Gives the player its power-up when touched, until the level restarts.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[require(LevelObject, Pickup)]
+pub struct PowerUpPickup {
+    pub power_up: PowerUp,
+}
+
+impl PowerUpPickup {
+    pub fn new(power_up: PowerUp) -> Self {
+        Self { power_up }
+    }
+}
+
+/// The power-up the player is holding, and the one currently in effect.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+pub struct PowerUpSlot {
+    pub held: Option<PowerUp>,
+    pub active: Option<ActivePowerUp>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub struct ActivePowerUp {
+    pub power_up: PowerUp,
+    /// Seconds until it wears off.
+    pub remaining: f32,
+}
+
+impl PowerUpSlot {
+    /// Uses the held power-up, replacing any that is still active.
+    pub fn activate(&mut self) {
+        if let Some(power_up) = self.held.take() {
+            info!("Activated {}", power_up.kind);
+            self.active = Some(ActivePowerUp {
+                power_up,
+                remaining: power_up.duration,
+            });
+        }
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        if let Some(active) = &mut self.active {
+            active.remaining -= delta;
+            if active.remaining <= 0.0 {
+                self.active = None;
+            }
+        }
+    }
+
+    pub fn jump_scale(&self) -> f32 {
+        self.active.map_or(1.0, |active| active.power_up.jump)
+    }
+
+    pub fn acceleration_scale(&self) -> f32 {
+        self.active
+            .map_or(1.0, |active| active.power_up.acceleration)
+    }
+
+    pub fn gravity_scale(&self) -> f32 {
+        self.active.map_or(1.0, |active| active.power_up.gravity)
+    }
+
+    pub fn bounce(&self) -> Option<f32> {
+        self.active.and_then(|active| active.power_up.bounce)
+    }
+}
+
+fn pick_up_power_up(
+    touched: On<PickupTouched>,
+    mut pickups: Query<(&PowerUpPickup, &mut Pickup)>,
+    mut players: Query<&mut PowerUpSlot, With<Player>>,
+) {
+    if let Ok((power_up, mut pickup)) = pickups.get_mut(touched.entity)
+        && let Ok(mut slot) = players.get_mut(touched.player)
+        && slot.held.is_none()
+    {
+        info!("Picked up {}", power_up.power_up.kind);
+        slot.held = Some(power_up.power_up);
+        pickup.taken = true;
+    }
+}
+
+fn activate_power_up(players: Query<&mut PowerUpSlot, With<Player>>, tick: Res<TickInput>) {
+    if tick.activate {
+        for mut slot in players {
+            slot.activate();
+        }
+    }
+}
+
+fn tick_power_up(players: Query<&mut PowerUpSlot, With<Player>>, time: Res<Time>) {
+    for mut slot in players {
+        if slot.active.is_some() {
+            slot.tick(time.delta_secs());
+        }
+    }
+}
+
+fn apply_power_up_physics(
+    mut cmd: Commands,
+    mut applied: Local<EntityHashMap<Option<PowerUp>>>,
+    players: Query<(Entity, &PowerUpSlot), (With<Player>, Changed<PowerUpSlot>)>,
+) {
+    for (player, slot) in players {
+        // the slot changes every tick while a power-up wears off, the physics only change with it
+        let active = slot.active.map(|active| active.power_up);
+        if applied.insert(player, active) == Some(active) {
+            continue;
+        }
+
+        let restitution = slot.bounce().map_or_else(Restitution::default, |bounce| {
+            let combine = combine_rule(bounce, Restitution::default().coefficient);
+            Restitution::new(bounce).with_combine_rule(combine)
+        });
+        cmd.entity(player)
+            .insert((GravityScale(slot.gravity_scale()), restitution));
+    }
+}
+
+fn reset_power_ups(_on: On<LevelRestartEvent>, players: Query<&mut PowerUpSlot, With<Player>>) {
+    for mut slot in players {
+        *slot = PowerUpSlot::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_up_wears_off() {
+        let mut slot = PowerUpSlot {
+            held: Some(PowerUpKind::SuperJump.power_up()),
+            active: None,
+        };
+        assert_eq!(slot.jump_scale(), 1.0);
+
+        slot.activate();
+        assert!(slot.held.is_none());
+        assert_eq!(slot.jump_scale(), 2.0);
+
+        slot.tick(DEFAULT_POWER_UP_DURATION / 2.0);
+        assert_eq!(slot.jump_scale(), 2.0);
+
+        slot.tick(DEFAULT_POWER_UP_DURATION);
+        assert!(slot.active.is_none());
+        assert_eq!(slot.jump_scale(), 1.0);
+    }
+}
@@ -286,12 +286,33 @@ pub trait BindErrorExt {
 
     fn parse_error(&self, msg: impl Display, span: SourceSpan) -> KdlBindError;
 
+    fn not_positive(&self, value: impl Display, span: SourceSpan) -> KdlBindError;
+
+    fn zero_direction(&self, span: SourceSpan) -> KdlBindError;
+
+    fn out_of_range(
+        &self,
+        value: impl Display,
+        min: impl Display,
+        max: impl Display,
+        span: SourceSpan,
+    ) -> KdlBindError;
+
     fn not_a_variant<T: Display>(
         &self,
         provided: impl Display,
         variants: &[T],
         span: SourceSpan,
     ) -> KdlBindError;
+
+    fn unknown_reference(
+        &self,
+        kind: impl Display,
+        id: impl Display,
+        span: SourceSpan,
+    ) -> KdlBindError;
+
+    fn duplicate_id(&self, kind: impl Display, id: impl Display, span: SourceSpan) -> KdlBindError;
 }
 
 impl BindErrorExt for Arc<String> {
@@ -342,6 +363,33 @@ impl BindErrorExt for Arc<String> {
         self.err(format!("Element value parsing error: {}", msg), Some(span))
     }
 
+    fn not_positive(&self, value: impl Display, span: SourceSpan) -> KdlBindError {
+        self.err(
+            format!("Element value is {} but should be greater than zero", value),
+            Some(span),
+        )
+    }
+
+    fn zero_direction(&self, span: SourceSpan) -> KdlBindError {
+        self.err("Direction can't be zero".to_string(), Some(span))
+    }
+
+    fn out_of_range(
+        &self,
+        value: impl Display,
+        min: impl Display,
+        max: impl Display,
+        span: SourceSpan,
+    ) -> KdlBindError {
+        self.err(
+            format!(
+                "Element value is {} but should be between {} and {}",
+                value, min, max
+            ),
+            Some(span),
+        )
+    }
+
     fn not_a_variant<T: Display>(
         &self,
         provided: impl Display,
@@ -378,4 +426,17 @@ impl BindErrorExt for Arc<String> {
             Some(span),
         )
     }
+
+    fn unknown_reference(
+        &self,
+        kind: impl Display,
+        id: impl Display,
+        span: SourceSpan,
+    ) -> KdlBindError {
+        self.err(format!("No {} with id '{}'", kind, id), Some(span))
+    }
+
+    fn duplicate_id(&self, kind: impl Display, id: impl Display, span: SourceSpan) -> KdlBindError {
+        self.err(format!("Duplicate {} id '{}'", kind, id), Some(span))
+    }
 }
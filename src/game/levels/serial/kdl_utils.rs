@@ -5,6 +5,8 @@ use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBin
 use bevy::asset::LoadContext;
 use bevy::prelude::*;
 use kdl::{KdlDocument, KdlEntry, KdlNode, KdlValue, NodeKey};
+use miette::SourceSpan;
+use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -172,6 +174,18 @@ pub trait KdlNodeExt {
         source: &Arc<String>,
     ) -> Result<Option<f64>, KdlBindError>;
 
+    fn must_get_positive_number(
+        &self,
+        key: impl Into<NodeKey>,
+        source: &Arc<String>,
+    ) -> Result<f64, KdlBindError>;
+
+    fn get_positive_number(
+        &self,
+        key: impl Into<NodeKey>,
+        source: &Arc<String>,
+    ) -> Result<Option<f64>, KdlBindError>;
+
     fn must_get_string(
         &self,
         key: impl Into<NodeKey>,
@@ -184,6 +198,24 @@ pub trait KdlNodeExt {
         source: &Arc<String>,
     ) -> Result<Option<&str>, KdlBindError>;
 
+    fn get_bool(
+        &self,
+        key: impl Into<NodeKey>,
+        source: &Arc<String>,
+    ) -> Result<Option<bool>, KdlBindError>;
+
+    fn must_get_id(
+        &self,
+        key: impl Into<NodeKey>,
+        source: &Arc<String>,
+    ) -> Result<SerialId, KdlBindError>;
+
+    fn get_id(
+        &self,
+        key: impl Into<NodeKey>,
+        source: &Arc<String>,
+    ) -> Result<Option<SerialId>, KdlBindError>;
+
     fn must_get_parse<T: FromStr>(
         &self,
         key: impl Into<NodeKey>,
@@ -228,6 +260,13 @@ pub trait KdlNodeExt {
         source: &Arc<String>,
     ) -> Result<Option<&'t T>, KdlBindError>;
 
+    /// A non-zero vector, normalized.
+    fn must_get_direction(
+        &self,
+        arg_offset: usize,
+        source: &Arc<String>,
+    ) -> Result<Dir3, KdlBindError>;
+
     fn must_get_vec3(&self, arg_offset: usize, source: &Arc<String>) -> Result<Vec3, KdlBindError> {
         let x = self.must_get_number(arg_offset, source);
         let y = self.must_get_number(arg_offset + 1, source);
@@ -339,6 +378,23 @@ impl KdlNodeExt for KdlNode {
             .map_or(Ok(None), |e| e.as_number(source).map(Some))
     }
 
+    fn must_get_positive_number(
+        &self,
+        key: impl Into<NodeKey>,
+        source: &Arc<String>,
+    ) -> Result<f64, KdlBindError> {
+        self.must_entry(key, source)?.as_positive_number(source)
+    }
+
+    fn get_positive_number(
+        &self,
+        key: impl Into<NodeKey>,
+        source: &Arc<String>,
+    ) -> Result<Option<f64>, KdlBindError> {
+        self.entry(key)
+            .map_or(Ok(None), |e| e.as_positive_number(source).map(Some))
+    }
+
     fn must_get_string(
         &self,
         key: impl Into<NodeKey>,
@@ -356,6 +412,32 @@ impl KdlNodeExt for KdlNode {
             .map_or(Ok(None), |e| e.as_string(source).map(Some))
     }
 
+    fn get_bool(
+        &self,
+        key: impl Into<NodeKey>,
+        source: &Arc<String>,
+    ) -> Result<Option<bool>, KdlBindError> {
+        self.entry(key)
+            .map_or(Ok(None), |e| e.as_bool(source).map(Some))
+    }
+
+    fn must_get_id(
+        &self,
+        key: impl Into<NodeKey>,
+        source: &Arc<String>,
+    ) -> Result<SerialId, KdlBindError> {
+        self.must_entry(key, source)?.as_id(source)
+    }
+
+    fn get_id(
+        &self,
+        key: impl Into<NodeKey>,
+        source: &Arc<String>,
+    ) -> Result<Option<SerialId>, KdlBindError> {
+        self.entry(key)
+            .map_or(Ok(None), |e| e.as_id(source).map(Some))
+    }
+
     fn must_get_parse<T: FromStr>(
         &self,
         key: impl Into<NodeKey>,
@@ -417,6 +499,15 @@ impl KdlNodeExt for KdlNode {
         self.entry(key)
             .map_or(Ok(None), |e| e.as_variant(variants, source).map(Some))
     }
+
+    fn must_get_direction(
+        &self,
+        arg_offset: usize,
+        source: &Arc<String>,
+    ) -> Result<Dir3, KdlBindError> {
+        let vec = self.must_get_vec3(arg_offset, source)?;
+        Dir3::new(vec).map_err(|_| source.zero_direction(self.span()))
+    }
 }
 
 pub trait KdlEntryExt {
@@ -424,8 +515,14 @@ pub trait KdlEntryExt {
 
     fn as_number(&self, source: &Arc<String>) -> Result<f64, KdlBindError>;
 
+    fn as_positive_number(&self, source: &Arc<String>) -> Result<f64, KdlBindError>;
+
     fn as_string(&self, source: &Arc<String>) -> Result<&str, KdlBindError>;
 
+    fn as_bool(&self, source: &Arc<String>) -> Result<bool, KdlBindError>;
+
+    fn as_id(&self, source: &Arc<String>) -> Result<SerialId, KdlBindError>;
+
     fn as_parse<T: FromStr>(&self, source: &Arc<String>) -> Result<T, KdlBindError>
     where
         T::Err: Display;
@@ -458,6 +555,15 @@ impl KdlEntryExt for KdlEntry {
         })
     }
 
+    fn as_positive_number(&self, source: &Arc<String>) -> Result<f64, KdlBindError> {
+        let number = self.as_number(source)?;
+        if number > 0.0 {
+            Ok(number)
+        } else {
+            Err(source.not_positive(number, self.span()))
+        }
+    }
+
     fn as_string(&self, source: &Arc<String>) -> Result<&str, KdlBindError> {
         self.value().as_string().ok_or_else(|| {
             source.wrong_value_type(
@@ -468,6 +574,23 @@ impl KdlEntryExt for KdlEntry {
         })
     }
 
+    fn as_bool(&self, source: &Arc<String>) -> Result<bool, KdlBindError> {
+        self.value().as_bool().ok_or_else(|| {
+            source.wrong_value_type(
+                self.value().value_type(),
+                &[KdlValueType::Bool],
+                self.span(),
+            )
+        })
+    }
+
+    fn as_id(&self, source: &Arc<String>) -> Result<SerialId, KdlBindError> {
+        Ok(SerialId {
+            value: self.as_string(source)?.to_string(),
+            span: (self.span().offset(), self.span().len()),
+        })
+    }
+
     fn as_parse<T: FromStr>(&self, source: &Arc<String>) -> Result<T, KdlBindError>
     where
         T::Err: Display,
@@ -529,6 +652,51 @@ impl KdlValueExt for KdlValue {
     }
 }
 
+/// An id that names an object in a level, or refers to one, along with where it was written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Hash)]
+pub struct SerialId {
+    pub value: String,
+    /// Offset and length of the id in the level source.
+    pub span: (usize, usize),
+}
+
+impl SerialId {
+    pub fn span(&self) -> SourceSpan {
+        self.span.into()
+    }
+
+    /// Checks that this reference names one of the `known` ids of the given kind of object.
+    pub fn must_refer_to(
+        &self,
+        kind: &str,
+        known: &HashSet<&str>,
+        source: &Arc<String>,
+    ) -> Result<(), KdlBindError> {
+        if known.contains(self.value.as_str()) {
+            Ok(())
+        } else {
+            Err(source.unknown_reference(kind, &self.value, self.span()))
+        }
+    }
+
+    /// Collects the ids of a kind of object, failing on duplicates.
+    pub fn unique<'a>(
+        kind: &str,
+        ids: impl IntoIterator<Item = &'a SerialId>,
+        source: &Arc<String>,
+    ) -> Result<HashSet<&'a str>, KdlBindError> {
+        let mut known = HashSet::new();
+        let mut errors: Vec<Result<(), KdlBindError>> = vec![];
+        for id in ids {
+            if !known.insert(id.value.as_str()) {
+                errors.push(Err(source.duplicate_id(kind, &id.value, id.span())));
+            }
+        }
+        errors.merge().map(|_| known)
+    }
+}
+
 #[derive(Debug, Copy, Clone, strum::VariantArray, strum::Display)]
 #[strum(serialize_all = "snake_case")]
 pub enum KdlAxis {
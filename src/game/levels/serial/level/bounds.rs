@@ -0,0 +1,65 @@
+use crate::game::levels::death::LevelBounds;
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlDocument;
+use std::sync::Arc;
+
+/// The level's optional `bounds`, see [`LevelBounds`].
+///
+/// ```kdl
+/// bounds {
+///     min -50 -20 -200
+///     max 50 50 20
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Reflect)]
+#[reflect(Debug, Default, Clone)]
+pub struct SerialBounds {
+    pub bounds: Option<LevelBounds>,
+}
+
+impl SerialBounds {
+    pub fn bind(
+        doc: &KdlDocument,
+        _load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let Some(node) = doc.get("bounds") else {
+            return Ok(Self::default());
+        };
+
+        let min = node
+            .must_children(&source)
+            .and_then(|doc| doc.must_get("min", &source))
+            .and_then(|min| min.must_get_vec3(0, &source));
+
+        let max = node
+            .must_children(&source)
+            .and_then(|doc| doc.must_get("max", &source))
+            .and_then(|max| max.must_get_vec3(0, &source));
+
+        let (min, max) = (min, max).merge()?;
+
+        if min.cmpge(max).any() {
+            return Err(source.err(
+                "bounds min must be below max on every axis".to_string(),
+                Some(node.span()),
+            ));
+        }
+
+        Ok(Self {
+            bounds: Some(LevelBounds { min, max }),
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        if let Some(bounds) = self.bounds {
+            args.cmd.insert_resource(bounds);
+        } else {
+            args.cmd.remove_resource::<LevelBounds>();
+        }
+    }
+}
@@ -1,19 +1,36 @@
 use crate::game::assets::asset_ref;
-use crate::game::levels::button::{ControlledBy, LevelButton, LevelButtonDoor};
-use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
-use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::button::{
+    ButtonMode, ButtonPress, ButtonRequirement, LevelButton, LevelButtonDoor, PresserFilter,
+};
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt, SerialId};
 use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::serial::level::dynamic::SerialDynamicObject;
+use crate::game::levels::signal::SignalEmitter;
 use avian3d::prelude::*;
 use bevy::asset::LoadContext;
 use bevy::prelude::*;
 use kdl::KdlNode;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+/// A floor button, see [`ButtonPress`] for what it takes to press it.
+///
+/// ```kdl
+/// button gate pressed_by=crates min_mass=20 timed=3 { pos 0 0 -12; }
+/// ```
+///
+/// `pressed_by` is `any` by default, `player`, `dyn` or the `group` of some `dyn` objects.
+/// `latch=#true` keeps the button down once pressed.
 #[derive(Debug, Clone, Reflect)]
 #[reflect(Debug, Clone)]
 pub struct SerialButton {
-    pub name: String,
+    pub id: SerialId,
+    /// Signal emitted while the button is pressed.
+    pub signal: Option<SerialId>,
+    pub pressed_by: Option<SerialId>,
+    pub min_mass: Option<f32>,
+    pub mode: ButtonMode,
     pub trans: Transform,
     pub off_material: Handle<StandardMaterial>,
     pub on_material: Handle<StandardMaterial>,
@@ -22,11 +39,41 @@ pub struct SerialButton {
 #[derive(Debug, Clone, Reflect)]
 #[reflect(Debug, Clone)]
 pub struct SerialButtonDoor {
-    pub name: String,
+    pub id: SerialId,
     pub trans: Transform,
     pub open_trans: Transform,
     pub dimensions: Vec3,
     pub material: Handle<StandardMaterial>,
+    pub requires: SerialRequirement,
+}
+
+/// Nodes that can make up a door's `requires` block.
+const REQUIREMENT_NODES: [&str; 7] = ["button", "signal", "all", "any", "not", "toggle", "latch"];
+
+/// A door's `requires` block, see [`ButtonRequirement`].
+///
+/// ```kdl
+/// requires {
+///     any {
+///         button left
+///         latch { button right; }
+///     }
+/// }
+/// ```
+///
+/// Nodes that take inputs need all of their children to be met, so `not { button a; button b; }`
+/// is met unless both buttons are pressed.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+#[reflect(no_field_bounds)]
+pub enum SerialRequirement {
+    Button(SerialId),
+    Signal(SerialId),
+    All(Vec<SerialRequirement>),
+    Any(Vec<SerialRequirement>),
+    Not(Vec<SerialRequirement>),
+    Toggle(Vec<SerialRequirement>),
+    Latch(Vec<SerialRequirement>),
 }
 
 impl SerialButton {
@@ -35,9 +82,29 @@ impl SerialButton {
         load_context: &mut LoadContext,
         source: Arc<String>,
     ) -> Result<Self, KdlBindError> {
-        let name = node
-            .must_get_string(0, &source)
-            .map(|name| name.to_string());
+        let id = node.must_get_id(0, &source);
+
+        let signal = node.get_id("signal", &source);
+
+        let pressed_by = node.get_id("pressed_by", &source);
+
+        let min_mass = node
+            .get_positive_number("min_mass", &source)
+            .map(|min_mass| min_mass.map(|min_mass| min_mass as f32));
+
+        let latch = node.get_bool("latch", &source);
+        let timed = node.get_positive_number("timed", &source);
+        let mode = (latch, timed).merge().and_then(|(latch, timed)| {
+            match (latch.unwrap_or_default(), timed) {
+                (true, Some(_)) => Err(source.err(
+                    "a button can't both latch and be timed".to_string(),
+                    node.entry("timed").map(|entry| entry.span()),
+                )),
+                (true, None) => Ok(ButtonMode::Latch),
+                (false, Some(seconds)) => Ok(ButtonMode::Timed(seconds as f32)),
+                (false, None) => Ok(ButtonMode::Momentary),
+            }
+        });
 
         let off_material = node.get_handle("off", load_context, &source).map(|handle| {
             handle.unwrap_or_else(|| asset_ref::default_plane_material(load_context))
@@ -52,11 +119,24 @@ impl SerialButton {
             .map_or(Ok(None), |doc| doc.get_transform(&source).map(Some))
             .map(|trans| trans.unwrap_or_default());
 
-        let (name, trans, off_material, on_material) =
-            (name, trans, off_material, on_material).merge()?;
+        let (id, signal, pressed_by, min_mass, mode, trans, off_material, on_material) = (
+            id,
+            signal,
+            pressed_by,
+            min_mass,
+            mode,
+            trans,
+            off_material,
+            on_material,
+        )
+            .merge()?;
 
         Ok(Self {
-            name,
+            id,
+            signal,
+            pressed_by,
+            min_mass,
+            mode,
             trans,
             off_material,
             on_material,
@@ -64,15 +144,32 @@ impl SerialButton {
     }
 
     pub fn spawn(&self, args: &mut LevelBuildArgs) -> Entity {
-        args.cmd
-            .spawn((
-                LevelButton {
-                    on_material: self.on_material.clone(),
-                    off_material: self.off_material.clone(),
-                },
-                self.trans,
-            ))
-            .id()
+        let mut commands = args.cmd.spawn((
+            LevelButton {
+                on_material: self.on_material.clone(),
+                off_material: self.off_material.clone(),
+            },
+            ButtonPress {
+                pressed_by: self.presser_filter(),
+                min_mass: self.min_mass,
+                mode: self.mode,
+                held: 0.0,
+            },
+            self.trans,
+        ));
+        if let Some(signal) = &self.signal {
+            commands.insert(SignalEmitter::new(&signal.value));
+        }
+        commands.id()
+    }
+
+    pub fn presser_filter(&self) -> PresserFilter {
+        match self.pressed_by.as_ref().map(|id| id.value.as_str()) {
+            None | Some("any") => PresserFilter::Any,
+            Some("player") => PresserFilter::Player,
+            Some("dyn") => PresserFilter::Dyn,
+            Some(group) => PresserFilter::Group(group.to_string()),
+        }
     }
 }
 
@@ -82,9 +179,7 @@ impl SerialButtonDoor {
         load_context: &mut LoadContext,
         source: Arc<String>,
     ) -> Result<Self, KdlBindError> {
-        let name = node
-            .must_get_string(0, &source)
-            .map(|name| name.to_string());
+        let id = node.must_get_id(0, &source);
 
         let trans = node
             .must_children(&source)
@@ -104,32 +199,171 @@ impl SerialButtonDoor {
                 handle.unwrap_or_else(|| asset_ref::default_plane_material(load_context))
             });
 
-        let (name, trans, open_trans, dimensions, material) =
-            (name, trans, open_trans, dimensions, material).merge()?;
+        // doors without a `requires` block are opened by the button with the same id
+        let requires = node.child("requires").map_or_else(
+            || node.must_get_id(0, &source).map(SerialRequirement::Button),
+            |requires| {
+                SerialRequirement::bind_children(requires, &source).map(SerialRequirement::All)
+            },
+        );
+
+        let (id, trans, open_trans, dimensions, material, requires) =
+            (id, trans, open_trans, dimensions, material, requires).merge()?;
 
         Ok(Self {
-            name,
+            id,
             trans,
             open_trans,
             dimensions,
             material,
+            requires,
         })
     }
 
-    pub fn spawn(&self, button_names: &HashMap<String, Entity>, args: &mut LevelBuildArgs) {
-        let mut commands = args.cmd.spawn((
+    /// Spawns the door, `buttons` needs to contain every button it refers to.
+    pub fn spawn(&self, buttons: &HashMap<String, Entity>, args: &mut LevelBuildArgs) {
+        args.cmd.spawn((
             LevelButtonDoor {
                 default_trans: self.trans,
                 open_trans: self.open_trans,
                 openness: 0.0,
+                requires: self.requires.resolve(buttons),
+                open: false,
             },
             Mesh3d(args.assets.add(Cuboid::from_size(self.dimensions).into())),
             MeshMaterial3d(self.material.clone()),
             RigidBody::Kinematic,
             Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z),
         ));
-        if let Some(button) = button_names.get(&self.name) {
-            commands.insert(ControlledBy(*button));
+    }
+}
+
+impl SerialRequirement {
+    pub fn bind(node: &KdlNode, source: &Arc<String>) -> Result<Self, KdlBindError> {
+        match node.name().value() {
+            "button" => node.must_get_id(0, source).map(SerialRequirement::Button),
+            "signal" => node.must_get_id(0, source).map(SerialRequirement::Signal),
+            "all" => Self::bind_children(node, source).map(SerialRequirement::All),
+            "any" => Self::bind_children(node, source).map(SerialRequirement::Any),
+            "not" => Self::bind_children(node, source).map(SerialRequirement::Not),
+            "toggle" => Self::bind_children(node, source).map(SerialRequirement::Toggle),
+            "latch" => Self::bind_children(node, source).map(SerialRequirement::Latch),
+            name => Err(source.not_a_variant(name, &REQUIREMENT_NODES, node.name().span())),
+        }
+    }
+
+    fn bind_children(node: &KdlNode, source: &Arc<String>) -> Result<Vec<Self>, KdlBindError> {
+        node.must_children(source)?
+            .nodes()
+            .iter()
+            .map(|child| Self::bind(child, source))
+            .collect::<Vec<_>>()
+            .merge()
+    }
+
+    /// Every button this refers to.
+    pub fn buttons(&self) -> Vec<&SerialId> {
+        self.leaves()
+            .into_iter()
+            .filter_map(|leaf| match leaf {
+                SerialRequirement::Button(id) => Some(id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Every signal this refers to.
+    pub fn signals(&self) -> Vec<&SerialId> {
+        self.leaves()
+            .into_iter()
+            .filter_map(|leaf| match leaf {
+                SerialRequirement::Signal(id) => Some(id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn leaves(&self) -> Vec<&SerialRequirement> {
+        match self {
+            SerialRequirement::Button(_) | SerialRequirement::Signal(_) => vec![self],
+            SerialRequirement::All(inputs)
+            | SerialRequirement::Any(inputs)
+            | SerialRequirement::Not(inputs)
+            | SerialRequirement::Toggle(inputs)
+            | SerialRequirement::Latch(inputs) => {
+                inputs.iter().flat_map(SerialRequirement::leaves).collect()
+            }
         }
     }
+
+    pub fn resolve(&self, buttons: &HashMap<String, Entity>) -> ButtonRequirement {
+        let resolve_all = |inputs: &[SerialRequirement]| {
+            inputs
+                .iter()
+                .map(|input| input.resolve(buttons))
+                .collect::<Vec<_>>()
+        };
+
+        match self {
+            SerialRequirement::Button(id) => ButtonRequirement::Button(buttons[&id.value]),
+            SerialRequirement::Signal(id) => ButtonRequirement::Signal(id.value.clone()),
+            SerialRequirement::All(inputs) => ButtonRequirement::All(resolve_all(inputs)),
+            SerialRequirement::Any(inputs) => ButtonRequirement::Any(resolve_all(inputs)),
+            SerialRequirement::Not(inputs) => ButtonRequirement::Not(resolve_all(inputs)),
+            SerialRequirement::Toggle(inputs) => ButtonRequirement::Toggle {
+                inputs: resolve_all(inputs),
+                was_met: false,
+                on: false,
+            },
+            SerialRequirement::Latch(inputs) => ButtonRequirement::Latch {
+                inputs: resolve_all(inputs),
+                latched: false,
+            },
+        }
+    }
+}
+
+/// Checks that button ids are unique and that every door only refers to buttons that exist.
+pub fn check_button_ids(
+    buttons: &[SerialButton],
+    button_doors: &[SerialButtonDoor],
+    source: &Arc<String>,
+) -> Result<(), KdlBindError> {
+    let button_ids = SerialId::unique("button", buttons.iter().map(|button| &button.id), source);
+    let door_ids = SerialId::unique(
+        "button_door",
+        button_doors.iter().map(|door| &door.id),
+        source,
+    );
+    let (button_ids, _) = (button_ids, door_ids).merge()?;
+
+    button_doors
+        .iter()
+        .flat_map(|door| door.requires.buttons())
+        .map(|id| id.must_refer_to("button", &button_ids, source))
+        .collect::<Vec<_>>()
+        .merge()
+        .map(|_| ())
+}
+
+/// Checks that buttons are only pressed by groups that some `dyn` objects are in.
+pub fn check_presser_groups(
+    buttons: &[SerialButton],
+    dynamic_objects: &[SerialDynamicObject],
+    source: &Arc<String>,
+) -> Result<(), KdlBindError> {
+    let groups = dynamic_objects
+        .iter()
+        .filter_map(|dynamic_object| dynamic_object.group.as_ref())
+        .map(|group| group.value.as_str())
+        .collect::<HashSet<_>>();
+
+    buttons
+        .iter()
+        .filter(|button| matches!(button.presser_filter(), PresserFilter::Group(_)))
+        .filter_map(|button| button.pressed_by.as_ref())
+        .map(|group| group.must_refer_to("group", &groups, source))
+        .collect::<Vec<_>>()
+        .merge()
+        .map(|_| ())
 }
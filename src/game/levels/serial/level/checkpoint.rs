@@ -0,0 +1,59 @@
+use crate::game::levels::checkpoint::Checkpoint;
+use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialCheckpoint {
+    pub dimensions: Vec3,
+    pub trans: Transform,
+    pub respawn: Transform,
+}
+
+impl SerialCheckpoint {
+    pub fn bind(
+        node: &KdlNode,
+        _load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let dimensions = node.must_get_scale(0, &source);
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let respawn = node
+            .child("respawn")
+            .and_then(|respawn| respawn.children())
+            .map_or(Ok(None), |doc| doc.get_transform(&source).map(Some));
+
+        let (dimensions, trans, respawn) = (dimensions, trans, respawn).merge()?;
+
+        // without an explicit respawn point, respawn in the middle of the checkpoint volume
+        let respawn = respawn.unwrap_or_else(|| {
+            Transform::from_translation(trans.translation).with_rotation(trans.rotation)
+        });
+
+        Ok(Self {
+            dimensions,
+            trans,
+            respawn,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        args.cmd.spawn((
+            Checkpoint {
+                respawn: self.respawn,
+            },
+            self.trans,
+            Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z),
+        ));
+    }
+}
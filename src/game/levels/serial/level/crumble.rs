@@ -0,0 +1,124 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::crumble::{CrumbleEnd, CrumbleState, CrumbleTrigger, Crumbling};
+use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::serial::level::surface::SerialSurface;
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+
+/// Nodes that bind to a [`SerialCrumble`].
+pub const CRUMBLE_NODES: [&str; 2] = ["crumble", "breakable"];
+
+/// A cuboid platform that goes away, see [`Crumbling`].
+///
+/// ```kdl
+/// // shakes for half a second after the player lands on it, then falls
+/// crumble 4 0.5 4 delay=0.5 respawn=3 { pos 0 0 -10; }
+/// crumble 4 0.5 4 then=vanish { pos 0 0 -16; }
+/// // shatters when something hits it at 8 m/s or more
+/// breakable 2 2 0.25 threshold=8 { pos 0 1 -20; }
+/// ```
+///
+/// Crumbling platforms shake for a second by default and either `fall` or `vanish`. Breakable
+/// platforms shatter at 6 m/s by default. Neither comes back unless they have a `respawn` time.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialCrumble {
+    pub trigger: CrumbleTrigger,
+    pub end: CrumbleEnd,
+    pub respawn: Option<f32>,
+    pub dimensions: Vec3,
+    pub material: Handle<StandardMaterial>,
+    pub trans: Transform,
+    pub surface: SerialSurface,
+}
+
+impl SerialCrumble {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let breakable = node.name().value() == "breakable";
+
+        let trigger = if breakable {
+            node.get_positive_number("threshold", &source)
+                .map(|threshold| CrumbleTrigger::Impact {
+                    threshold: threshold.unwrap_or(6.0) as f32,
+                })
+        } else {
+            node.get_positive_number("delay", &source)
+                .map(|delay| CrumbleTrigger::Landing {
+                    delay: delay.unwrap_or(1.0) as f32,
+                })
+        };
+
+        let end = if breakable {
+            Ok(CrumbleEnd::Shatter)
+        } else {
+            node.get_variant("then", &[CrumbleEnd::Fall, CrumbleEnd::Vanish], &source)
+                .map(|end| end.copied().unwrap_or_default())
+        };
+
+        let respawn = node
+            .get_positive_number("respawn", &source)
+            .map(|respawn| respawn.map(|respawn| respawn as f32));
+
+        let dimensions = node.must_get_scale(0, &source);
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| {
+                handle.unwrap_or_else(|| asset_ref::default_plane_material(load_context))
+            });
+
+        let trans = node
+            .children()
+            .map_or(Ok(None), |doc| doc.get_transform(&source).map(Some))
+            .map(|trans| trans.unwrap_or_default());
+
+        let surface = SerialSurface::bind(node, load_context, &source);
+
+        let (trigger, end, respawn, dimensions, material, trans, surface) =
+            (trigger, end, respawn, dimensions, material, trans, surface).merge()?;
+
+        let surface = surface.with_material(&material, load_context);
+
+        Ok(Self {
+            trigger,
+            end,
+            respawn,
+            dimensions,
+            material,
+            trans,
+            surface,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        let surface = self.surface.resolve(args).physics();
+        args.cmd.spawn((
+            Crumbling {
+                trigger: self.trigger,
+                end: self.end,
+                respawn: self.respawn,
+                dimensions: self.dimensions,
+                home: self.trans,
+                state: CrumbleState::Solid,
+            },
+            self.trans,
+            Visibility::default(),
+            RigidBody::Static,
+            Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z),
+            surface,
+            children![(
+                Mesh3d(args.assets.add(Cuboid::from_size(self.dimensions).into())),
+                MeshMaterial3d(self.material.clone()),
+            )],
+        ));
+    }
+}
@@ -3,6 +3,7 @@ use crate::game::levels::LevelObject;
 use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
 use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
 use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::serial::level::surface::SerialSurface;
 use avian3d::prelude::*;
 use bevy::asset::LoadContext;
 use bevy::prelude::*;
@@ -15,6 +16,7 @@ pub struct SerialCuboid {
     pub material: Handle<StandardMaterial>,
     pub dimensions: Vec3,
     pub trans: Transform,
+    pub surface: SerialSurface,
 }
 
 impl SerialCuboid {
@@ -36,16 +38,23 @@ impl SerialCuboid {
             .map_or(Ok(None), |doc| doc.get_transform(&source).map(Some))
             .map(|trans| trans.unwrap_or_default());
 
-        let (material, dimensions, trans) = (material, dimensions, trans).merge()?;
+        let surface = SerialSurface::bind(node, load_context, &source);
+
+        let (material, dimensions, trans, surface) =
+            (material, dimensions, trans, surface).merge()?;
+
+        let surface = surface.with_material(&material, load_context);
 
         Ok(Self {
             material,
             dimensions,
             trans,
+            surface,
         })
     }
 
     pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        let surface = self.surface.resolve(args).physics();
         args.cmd.spawn((
             LevelObject,
             self.trans,
@@ -53,6 +62,7 @@ impl SerialCuboid {
             MeshMaterial3d(self.material.clone()),
             RigidBody::Static,
             Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z),
+            surface,
         ));
     }
 }
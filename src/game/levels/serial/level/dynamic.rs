@@ -1,9 +1,12 @@
 use crate::game::assets::asset_ref;
-use crate::game::levels::LevelObject;
-use crate::game::levels::button::ButtonPresser;
+use crate::game::levels::DynamicLevelObject;
+use crate::game::levels::button::{ButtonPresser, PresserGroup};
+use crate::game::levels::death::Respawn;
+use crate::game::levels::gravity::GravityUp;
 use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
-use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt, SerialId};
 use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::serial::level::surface::SerialSurface;
 use avian3d::prelude::*;
 use bevy::asset::LoadContext;
 use bevy::prelude::*;
@@ -14,10 +17,19 @@ use strum::VariantArray;
 #[derive(Debug, Clone, Reflect)]
 #[reflect(Debug, Clone)]
 pub struct SerialDynamicObject {
+    /// Lets joints refer to the object.
+    pub id: Option<SerialId>,
     pub ty: DynamicObjectType,
     pub dimensions: Vec3,
     pub trans: Transform,
     pub material: Handle<StandardMaterial>,
+    pub surface: SerialSurface,
+    /// Whether the object goes back to `trans` when it's killed, rather than being lost.
+    pub respawn: bool,
+    /// Lets buttons only be pressed by some objects.
+    pub group: Option<SerialId>,
+    /// Overrides the mass computed from the collider.
+    pub mass: Option<f32>,
 }
 
 #[derive(
@@ -37,6 +49,8 @@ impl SerialDynamicObject {
         load_context: &mut LoadContext,
         source: Arc<String>,
     ) -> Result<Self, KdlBindError> {
+        let id = node.get_id("id", &source);
+
         let ty = node
             .get_variant("type", DynamicObjectType::VARIANTS, &source)
             .map(|ty| ty.copied().unwrap_or_default());
@@ -56,27 +70,63 @@ impl SerialDynamicObject {
 
         let dimensions = node.get_scale(0).unwrap_or(Vec3::splat(0.25));
 
-        let (ty, material, trans) = (ty, material, trans).merge()?;
+        let surface = SerialSurface::bind(node, load_context, &source);
+
+        let respawn = node
+            .get_bool("respawn", &source)
+            .map(|respawn| respawn.unwrap_or_default());
+
+        let group = node.get_id("group", &source);
+
+        let mass = node
+            .get_positive_number("mass", &source)
+            .map(|mass| mass.map(|mass| mass as f32));
+
+        let (id, ty, material, trans, surface, respawn, group, mass) =
+            (id, ty, material, trans, surface, respawn, group, mass).merge()?;
+
+        let surface = surface.with_material(&material, load_context);
 
         Ok(Self {
+            id,
             ty,
             dimensions,
             trans,
             material,
+            surface,
+            respawn,
+            group,
+            mass,
         })
     }
 
-    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+    /// Spawns the object, unless it's kept from before a checkpoint restart.
+    pub fn spawn(&self, args: &mut LevelBuildArgs) -> Option<Entity> {
         if args.dyn_assets {
-            args.cmd.spawn((
-                LevelObject,
+            let surface = self.surface.resolve(args).physics();
+            let mut entity = args.cmd.spawn((
+                DynamicLevelObject,
                 self.trans,
                 ButtonPresser,
+                GravityUp::default(),
                 Mesh3d(args.assets.add(self.ty.to_mesh(self.dimensions))),
                 MeshMaterial3d(self.material.clone()),
                 RigidBody::Dynamic,
                 self.ty.to_collider(self.dimensions),
+                surface,
             ));
+            if self.respawn {
+                entity.insert(Respawn { home: self.trans });
+            }
+            if let Some(group) = &self.group {
+                entity.insert(PresserGroup(group.value.clone()));
+            }
+            if let Some(mass) = self.mass {
+                entity.insert(Mass(mass));
+            }
+            Some(entity.id())
+        } else {
+            None
         }
     }
 }
@@ -0,0 +1,116 @@
+use crate::game::levels::force::ForceField;
+use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+
+/// Nodes that bind to a [`SerialForceField`].
+pub const FORCE_FIELD_NODES: [&str; 4] = ["wind", "conveyor", "attractor", "repulsor"];
+
+/// A force field volume, see [`ForceField`].
+///
+/// ```kdl
+/// wind 12 2 2 8 falloff=0.5 {
+///     pos 0 1 -4
+///     direction 0 0 -1
+/// }
+/// conveyor 3 2 0.5 6 { pos 0 0.25 -10; }
+/// attractor 8 4 { pos 0 2 -20; }
+/// repulsor 8 4 { pos 4 2 -20; }
+/// ```
+///
+/// Wind and conveyors push along their `direction`, relative to the volume, and default to its
+/// forward direction.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialForceField {
+    pub field: ForceField,
+    /// Size of the volume, the radius is in `x` for attractors.
+    pub dimensions: Vec3,
+    pub trans: Transform,
+}
+
+impl SerialForceField {
+    pub fn bind(
+        node: &KdlNode,
+        _load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let name = node.name().value();
+
+        let strength = match name {
+            "conveyor" => node.must_get_number(0, &source),
+            _ => node.must_get_positive_number(0, &source),
+        };
+
+        let dimensions = match name {
+            "attractor" | "repulsor" => node
+                .must_get_positive_number(1, &source)
+                .map(|radius| Vec3::splat(radius as f32)),
+            _ => node.must_get_scale(1, &source),
+        };
+
+        let falloff = node
+            .get_number("falloff", &source)
+            .map(|falloff| falloff.unwrap_or_default().clamp(0.0, 1.0));
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let direction = node
+            .must_children(&source)
+            .map(|doc| doc.get("direction"))
+            .and_then(|direction| {
+                direction.map_or(Ok(Dir3::NEG_Z), |direction| {
+                    direction.must_get_direction(0, &source)
+                })
+            });
+
+        let (strength, dimensions, falloff, trans, direction) =
+            (strength, dimensions, falloff, trans, direction).merge()?;
+        let strength = strength as f32;
+
+        let field = match name {
+            "wind" => ForceField::Wind {
+                direction,
+                strength,
+                falloff: falloff as f32,
+                length: direction.abs().dot(dimensions),
+            },
+            "conveyor" => ForceField::Conveyor {
+                direction,
+                speed: strength,
+            },
+            "repulsor" => ForceField::Attractor {
+                strength: -strength,
+                radius: dimensions.x,
+            },
+            _ => ForceField::Attractor {
+                strength,
+                radius: dimensions.x,
+            },
+        };
+
+        Ok(Self {
+            field,
+            dimensions,
+            trans,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        let collider = match self.field {
+            ForceField::Attractor { radius, .. } => Collider::sphere(radius),
+            ForceField::Wind { .. } | ForceField::Conveyor { .. } => {
+                Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z)
+            }
+        };
+
+        args.cmd.spawn((self.field, self.trans, collider));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::gem::{Gem, GemCount};
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlEntryExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::{KdlDocument, KdlNode};
+use std::sync::Arc;
+
+/// The level's gems, and how many of them the finish point needs, see
+/// [`crate::game::levels::gem`].
+///
+/// ```kdl
+/// finish required=2 { pos 0 0 -30; }
+///
+/// gem { pos 0 1 -5; }
+/// gem scene="preload:physball" { pos 4 1 -10; }
+/// gem material="gem.material.json" { pos -4 1 -15; }
+/// ```
+///
+/// Without `required`, every gem is needed.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialGems {
+    pub required: u32,
+    pub gems: Vec<SerialGem>,
+}
+
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialGem {
+    pub trans: Transform,
+    /// Shown instead of the default gem mesh.
+    pub scene: Option<Handle<Scene>>,
+    pub material: Handle<StandardMaterial>,
+}
+
+impl SerialGems {
+    pub fn bind(
+        doc: &KdlDocument,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let gems = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "gem")
+            .map(|node| SerialGem::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge()?;
+
+        let total = gems.len() as u32;
+        let required = doc
+            .get("finish")
+            .and_then(|finish| finish.entry("required"))
+            .map_or(Ok(total), |entry| {
+                let required = entry.as_number(&source)?;
+                if required.fract() == 0.0 && (0.0..=total as f64).contains(&required) {
+                    Ok(required as u32)
+                } else {
+                    Err(source.out_of_range(required, 0, total, entry.span()))
+                }
+            })?;
+
+        Ok(Self { required, gems })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        // gems stay collected across checkpoint restarts
+        if args.dyn_assets {
+            args.cmd.insert_resource(GemCount {
+                required: self.required,
+                ..default()
+            });
+
+            for gem in self.gems.iter() {
+                gem.spawn(args);
+            }
+        }
+    }
+}
+
+impl SerialGem {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let scene = node.get_handle("scene", load_context, &source);
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| handle.unwrap_or_else(|| asset_ref::default_text_material(load_context)));
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let (scene, material, trans) = (scene, material, trans).merge()?;
+
+        Ok(Self {
+            trans,
+            scene,
+            material,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        let mut gem = args.cmd.spawn((Gem, self.trans));
+        if let Some(scene) = &self.scene {
+            gem.insert(SceneRoot(scene.clone()));
+        } else {
+            let mesh = Sphere::new(0.2)
+                .mesh()
+                .ico(0)
+                .expect("icosphere without subdivisions");
+            gem.insert((
+                Mesh3d(args.assets.add(mesh)),
+                MeshMaterial3d(self.material.clone()),
+            ));
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::game::levels::gravity::GravityZone;
+use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::{KdlDocument, KdlNode};
+use std::sync::Arc;
+
+/// The level's `gravity x y z` and its gravity zones, see [`crate::game::levels::gravity`].
+///
+/// ```kdl
+/// gravity 0 -9.81 0
+///
+/// gravity_zone 4 10 4 {
+///     pos 0 5 -10
+///     gravity 9.81 0 0
+/// }
+/// ```
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialGravity {
+    pub gravity: Vec3,
+    pub zones: Vec<SerialGravityZone>,
+}
+
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialGravityZone {
+    pub gravity: Vec3,
+    pub dimensions: Vec3,
+    pub trans: Transform,
+}
+
+impl SerialGravity {
+    pub fn bind(
+        doc: &KdlDocument,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let gravity = doc.get("gravity").map_or(Ok(Gravity::default().0), |node| {
+            node.must_get_vec3(0, &source)
+        });
+
+        let zones = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "gravity_zone")
+            .map(|node| SerialGravityZone::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let (gravity, zones) = (gravity, zones).merge()?;
+
+        Ok(Self { gravity, zones })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        args.cmd.insert_resource(Gravity(self.gravity));
+
+        for zone in self.zones.iter() {
+            zone.spawn(args);
+        }
+    }
+}
+
+impl SerialGravityZone {
+    pub fn contains(&self, point: Vec3) -> bool {
+        let local = self
+            .trans
+            .compute_affine()
+            .inverse()
+            .transform_point3(point);
+        local.abs().cmple(self.dimensions / 2.0).all()
+    }
+
+    pub fn bind(
+        node: &KdlNode,
+        _load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let dimensions = node.must_get_scale(0, &source);
+
+        let gravity = node
+            .must_children(&source)
+            .and_then(|doc| doc.must_get("gravity", &source))
+            .and_then(|gravity| gravity.must_get_vec3(0, &source));
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let (gravity, dimensions, trans) = (gravity, dimensions, trans).merge()?;
+
+        Ok(Self {
+            gravity,
+            dimensions,
+            trans,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        args.cmd.spawn((
+            GravityZone {
+                gravity: self.gravity,
+            },
+            self.trans,
+            Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z),
+        ));
+    }
+}
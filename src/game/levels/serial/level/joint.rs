@@ -0,0 +1,430 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::DynamicLevelObject;
+use crate::game::levels::joint::JointMotor;
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt, SerialId};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::serial::level::dynamic::SerialDynamicObject;
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::{KdlDocument, KdlNode};
+use std::collections::HashMap;
+use std::sync::Arc;
+use strum::VariantArray;
+
+/// Most angular acceleration a motor applies when the level doesn't say.
+const DEFAULT_MOTOR_STRENGTH: f64 = 10.0;
+
+/// Joints between `dyn` objects, and chains.
+///
+/// A joint holds a `dyn` object to another one, or to a fixed point in the world. The anchor is
+/// where the bodies are held together, in level space. Distance joints keep the anchor at a
+/// distance from the center of the object instead.
+///
+/// ```kdl
+/// dyn type=cube 4 0.2 1 id=seesaw { pos 0 1 -5; }
+/// joint revolute seesaw {
+///     anchor 0 1 -5
+///     axis 1 0 0
+///     limit -20 20
+/// }
+///
+/// dyn type=cube 0.1 2 1 id=door { pos 3 1 -5; }
+/// joint revolute door {
+///     anchor 3 1 -5.5
+///     motor 45 strength=5
+/// }
+///
+/// dyn 0.3 id=bob { pos 0 2 -10; }
+/// joint distance bob { anchor 0 5 -10; }
+///
+/// chain 6 0.4 radius=0.05 to=bob {
+///     pos 0 5 -12
+///     direction 0 -1 0
+/// }
+/// ```
+///
+/// Revolute joints turn around `axis`, which defaults to up. Their limits and motor speed are in
+/// degrees, distance joint limits are in meters.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialJoints {
+    pub joints: Vec<SerialJoint>,
+    pub chains: Vec<SerialChain>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Reflect, strum::VariantArray, strum::Display)]
+#[reflect(Debug, Clone, PartialEq, Hash)]
+#[strum(serialize_all = "snake_case")]
+pub enum JointKind {
+    Revolute,
+    Spherical,
+    Distance,
+    Fixed,
+}
+
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialJoint {
+    pub kind: JointKind,
+    pub body: SerialId,
+    /// Held to the world when unset.
+    pub other: Option<SerialId>,
+    pub anchor: Vec3,
+    pub axis: Dir3,
+    /// In radians for revolute joints.
+    pub limits: Option<(f32, f32)>,
+    pub motor: Option<SerialMotor>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub struct SerialMotor {
+    /// In radians per second.
+    pub speed: f32,
+    pub strength: f32,
+}
+
+/// Links hanging from a fixed point, optionally holding a `dyn` object at the other end.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialChain {
+    pub links: u32,
+    pub link_length: f32,
+    pub radius: f32,
+    pub to: Option<SerialId>,
+    pub material: Handle<StandardMaterial>,
+    pub top: Vec3,
+    pub direction: Dir3,
+}
+
+/// Spawned `dyn` objects by id, and where they were spawned.
+pub type JointBodies = HashMap<String, (Entity, Transform)>;
+
+impl SerialJoints {
+    pub fn bind(
+        doc: &KdlDocument,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let joints = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "joint")
+            .map(|node| SerialJoint::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let chains = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "chain")
+            .map(|node| SerialChain::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let (joints, chains) = (joints, chains).merge()?;
+
+        Ok(Self { joints, chains })
+    }
+
+    pub fn spawn(&self, bodies: &JointBodies, args: &mut LevelBuildArgs) {
+        // joints are kept along with the objects they hold together
+        if args.dyn_assets {
+            for joint in self.joints.iter() {
+                joint.spawn(bodies, args);
+            }
+
+            for chain in self.chains.iter() {
+                chain.spawn(bodies, args);
+            }
+        }
+    }
+
+    /// Every `dyn` object the joints refer to.
+    pub fn bodies(&self) -> impl Iterator<Item = &SerialId> {
+        let joints = self
+            .joints
+            .iter()
+            .flat_map(|joint| std::iter::once(&joint.body).chain(joint.other.as_ref()));
+        let chains = self.chains.iter().filter_map(|chain| chain.to.as_ref());
+        joints.chain(chains)
+    }
+}
+
+impl SerialJoint {
+    pub fn bind(
+        node: &KdlNode,
+        _load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let kind = node.must_get_variant(0, JointKind::VARIANTS, &source);
+
+        let body = node.must_get_id(1, &source);
+
+        let other = node.get_id(2, &source);
+
+        let anchor = node
+            .must_children(&source)
+            .and_then(|doc| doc.must_get("anchor", &source))
+            .and_then(|anchor| anchor.must_get_vec3(0, &source));
+
+        let axis = node.must_children(&source).and_then(|doc| {
+            doc.get("axis")
+                .map_or(Ok(Dir3::Y), |axis| axis.must_get_direction(0, &source))
+        });
+
+        let limit = node.must_children(&source).map(|doc| doc.get("limit"));
+
+        let motor = node.must_children(&source).map(|doc| doc.get("motor"));
+
+        let (kind, body, other, anchor, axis, limit, motor) =
+            (kind, body, other, anchor, axis, limit, motor).merge()?;
+
+        let limits = limit.map_or(Ok(None), |limit| {
+            if !matches!(kind, JointKind::Revolute | JointKind::Distance) {
+                return Err(source.err(
+                    format!("{} joints can't have limits", kind),
+                    Some(limit.span()),
+                ));
+            }
+            let min = limit.must_get_number(0, &source);
+            let max = limit.must_get_number(1, &source);
+            let (min, max) = (min, max).merge()?;
+            Ok(Some(match kind {
+                JointKind::Revolute => ((min as f32).to_radians(), (max as f32).to_radians()),
+                _ => (min as f32, max as f32),
+            }))
+        });
+
+        let motor = motor.map_or(Ok(None), |motor| {
+            if *kind != JointKind::Revolute {
+                return Err(source.err(
+                    format!("{} joints can't have a motor", kind),
+                    Some(motor.span()),
+                ));
+            }
+            let speed = motor.must_get_number(0, &source);
+            let strength = motor.get_positive_number("strength", &source);
+            let (speed, strength) = (speed, strength).merge()?;
+            Ok(Some(SerialMotor {
+                speed: (speed as f32).to_radians(),
+                strength: strength.unwrap_or(DEFAULT_MOTOR_STRENGTH) as f32,
+            }))
+        });
+
+        let (limits, motor) = (limits, motor).merge()?;
+
+        Ok(Self {
+            kind: *kind,
+            body,
+            other,
+            anchor,
+            axis,
+            limits,
+            motor,
+        })
+    }
+
+    pub fn spawn(&self, bodies: &JointBodies, args: &mut LevelBuildArgs) {
+        let Some(&(body2, trans2)) = bodies.get(&self.body.value) else {
+            return;
+        };
+        let (body1, trans1) = match &self.other {
+            Some(other) => {
+                let Some(&other) = bodies.get(&other.value) else {
+                    return;
+                };
+                other
+            }
+            None => {
+                // turned like the object, so the hinge axis lines up for both
+                let trans = Transform::from_translation(self.anchor).with_rotation(trans2.rotation);
+                (spawn_fixed_point(trans, args), trans)
+            }
+        };
+
+        let anchor1 = local_anchor(&trans1, self.anchor);
+        let anchor2 = local_anchor(&trans2, self.anchor);
+        let axis = trans2.rotation.inverse() * self.axis;
+
+        let mut joint = args.cmd.spawn((DynamicLevelObject, JointCollisionDisabled));
+        match self.kind {
+            JointKind::Revolute => {
+                let mut revolute = RevoluteJoint::new(body1, body2)
+                    .with_local_anchor1(anchor1)
+                    .with_local_anchor2(anchor2)
+                    .with_hinge_axis(axis.as_vec3());
+                if let Some((min, max)) = self.limits {
+                    revolute = revolute.with_angle_limits(min, max);
+                }
+                joint.insert(revolute);
+            }
+            JointKind::Spherical => {
+                joint.insert(
+                    SphericalJoint::new(body1, body2)
+                        .with_local_anchor1(anchor1)
+                        .with_local_anchor2(anchor2),
+                );
+            }
+            JointKind::Distance => {
+                let length = self.anchor.distance(trans2.translation);
+                let (min, max) = self.limits.unwrap_or((length, length));
+                joint.insert(
+                    DistanceJoint::new(body1, body2)
+                        .with_local_anchor1(anchor1)
+                        .with_local_anchor2(Vec3::ZERO)
+                        .with_limits(min, max),
+                );
+            }
+            JointKind::Fixed => {
+                joint.insert(
+                    FixedJoint::new(body1, body2)
+                        .with_local_anchor1(anchor1)
+                        .with_local_anchor2(anchor2),
+                );
+            }
+        }
+
+        if let Some(motor) = self.motor {
+            joint.insert(JointMotor {
+                body1,
+                body2,
+                axis: trans1.rotation.inverse() * self.axis,
+                speed: motor.speed,
+                strength: motor.strength,
+            });
+        }
+    }
+}
+
+impl SerialChain {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let links = node.must_get_positive_number(0, &source);
+
+        let link_length = node.must_get_positive_number(1, &source);
+
+        let radius = node.get_positive_number("radius", &source);
+
+        let to = node.get_id("to", &source);
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| {
+                handle.unwrap_or_else(|| asset_ref::default_plane_material(load_context))
+            });
+
+        let top = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source))
+            .map(|trans| trans.translation);
+
+        let direction = node.must_children(&source).and_then(|doc| {
+            doc.get("direction").map_or(Ok(Dir3::NEG_Y), |direction| {
+                direction.must_get_direction(0, &source)
+            })
+        });
+
+        let (links, link_length, radius, to, material, top, direction) =
+            (links, link_length, radius, to, material, top, direction).merge()?;
+
+        Ok(Self {
+            links: (links.round() as u32).max(1),
+            link_length: link_length as f32,
+            radius: radius.unwrap_or(0.05) as f32,
+            to,
+            material,
+            top,
+            direction,
+        })
+    }
+
+    pub fn spawn(&self, bodies: &JointBodies, args: &mut LevelBuildArgs) {
+        // links hang along their local -Y
+        let rotation = Quat::from_rotation_arc(Vec3::NEG_Y, *self.direction);
+        let half = Vec3::Y * self.link_length / 2.0;
+        let mesh = args
+            .assets
+            .add(Capsule3d::new(self.radius, self.link_length).into());
+
+        let mut previous = spawn_fixed_point(
+            Transform::from_translation(self.top).with_rotation(rotation),
+            args,
+        );
+        let mut previous_anchor = Vec3::ZERO;
+        for i in 0..self.links {
+            let center = self.top + self.direction * (i as f32 + 0.5) * self.link_length;
+            let link = args
+                .cmd
+                .spawn((
+                    DynamicLevelObject,
+                    Transform::from_translation(center).with_rotation(rotation),
+                    RigidBody::Dynamic,
+                    Collider::capsule(self.radius, self.link_length),
+                    Mesh3d(mesh.clone()),
+                    MeshMaterial3d(self.material.clone()),
+                ))
+                .id();
+            args.cmd.spawn((
+                DynamicLevelObject,
+                JointCollisionDisabled,
+                SphericalJoint::new(previous, link)
+                    .with_local_anchor1(previous_anchor)
+                    .with_local_anchor2(half),
+            ));
+            previous = link;
+            previous_anchor = -half;
+        }
+
+        if let Some(to) = &self.to
+            && let Some((body, trans)) = bodies.get(&to.value)
+        {
+            let end = self.top + self.direction * self.links as f32 * self.link_length;
+            args.cmd.spawn((
+                DynamicLevelObject,
+                JointCollisionDisabled,
+                SphericalJoint::new(previous, *body)
+                    .with_local_anchor1(previous_anchor)
+                    .with_local_anchor2(local_anchor(trans, end)),
+            ));
+        }
+    }
+}
+
+/// A static body for joints to hold things to the world.
+fn spawn_fixed_point(trans: Transform, args: &mut LevelBuildArgs) -> Entity {
+    args.cmd
+        .spawn((DynamicLevelObject, trans, RigidBody::Static))
+        .id()
+}
+
+/// `point` relative to a body spawned at `trans`.
+fn local_anchor(trans: &Transform, point: Vec3) -> Vec3 {
+    trans.rotation.inverse() * (point - trans.translation)
+}
+
+pub fn check_joint_ids(
+    dynamic_objects: &[SerialDynamicObject],
+    joints: &SerialJoints,
+    source: &Arc<String>,
+) -> Result<(), KdlBindError> {
+    let ids = SerialId::unique(
+        "dyn",
+        dynamic_objects
+            .iter()
+            .filter_map(|dynamic_object| dynamic_object.id.as_ref()),
+        source,
+    )?;
+
+    joints
+        .bodies()
+        .map(|body| body.must_refer_to("dyn", &ids, source))
+        .collect::<Vec<_>>()
+        .merge()
+        .map(|_| ())
+}
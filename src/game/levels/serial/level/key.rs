@@ -0,0 +1,167 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::button::LevelButtonDoor;
+use crate::game::levels::key::{Key, KeyPickup, LockedDoor};
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlEntryExt, KdlNodeExt, SerialId};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A key pickup. Several pickups can have the same key.
+///
+/// ```kdl
+/// key red color="#e03030" { pos 0 1 -10; }
+/// ```
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialKey {
+    pub id: SerialId,
+    pub color: Color,
+    pub trans: Transform,
+}
+
+/// A door that slides open once the player touches it holding its key.
+///
+/// ```kdl
+/// locked_door red 2 3 0.25 {
+///     default { pos 0 1.5 -20; }
+///     open { pos 0 4.5 -20; }
+/// }
+/// ```
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialLockedDoor {
+    pub key: SerialId,
+    pub trans: Transform,
+    pub open_trans: Transform,
+    pub dimensions: Vec3,
+    pub material: Handle<StandardMaterial>,
+}
+
+impl SerialKey {
+    pub fn bind(
+        node: &KdlNode,
+        _load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let id = node.must_get_id(0, &source);
+
+        let color = node
+            .entry("color")
+            .map_or(Ok(Color::srgb(1.0, 0.8, 0.2)), |entry| {
+                let hex = entry.as_string(&source)?;
+                Srgba::hex(hex)
+                    .map(Color::from)
+                    .map_err(|err| source.parse_error(err, entry.span()))
+            });
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let (id, color, trans) = (id, color, trans).merge()?;
+
+        Ok(Self { id, color, trans })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        let material = args.assets.add(StandardMaterial {
+            base_color: self.color,
+            emissive: self.color.to_linear() * 0.5,
+            ..default()
+        });
+
+        args.cmd.spawn((
+            KeyPickup {
+                key: Key {
+                    id: self.id.value.clone(),
+                    color: self.color,
+                },
+            },
+            self.trans,
+            Mesh3d(args.assets.add(Torus::new(0.1, 0.25).into())),
+            MeshMaterial3d(material),
+        ));
+    }
+}
+
+impl SerialLockedDoor {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let key = node.must_get_id(0, &source);
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|children| children.must_children("default", &source))
+            .and_then(|default| default.get_transform(&source));
+
+        let open_trans = node
+            .must_children(&source)
+            .and_then(|children| children.must_children("open", &source))
+            .and_then(|open| open.get_transform(&source));
+
+        let dimensions = node.must_get_scale(1, &source);
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| {
+                handle.unwrap_or_else(|| asset_ref::default_plane_material(load_context))
+            });
+
+        let (key, trans, open_trans, dimensions, material) =
+            (key, trans, open_trans, dimensions, material).merge()?;
+
+        Ok(Self {
+            key,
+            trans,
+            open_trans,
+            dimensions,
+            material,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        args.cmd.spawn((
+            LockedDoor {
+                key: self.key.value.clone(),
+            },
+            LevelButtonDoor {
+                default_trans: self.trans,
+                open_trans: self.open_trans,
+                openness: 0.0,
+                requires: LockedDoor::locked(),
+                open: false,
+            },
+            Mesh3d(args.assets.add(Cuboid::from_size(self.dimensions).into())),
+            MeshMaterial3d(self.material.clone()),
+            RigidBody::Kinematic,
+            Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z),
+        ));
+    }
+}
+
+/// Checks that every locked door has a key somewhere in the level.
+pub fn check_key_ids(
+    keys: &[SerialKey],
+    locked_doors: &[SerialLockedDoor],
+    source: &Arc<String>,
+) -> Result<(), KdlBindError> {
+    let key_ids = keys
+        .iter()
+        .map(|key| key.id.value.as_str())
+        .collect::<HashSet<_>>();
+
+    locked_doors
+        .iter()
+        .map(|door| door.key.must_refer_to("key", &key_ids, source))
+        .collect::<Vec<_>>()
+        .merge()
+        .map(|_| ())
+}
@@ -0,0 +1,196 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::launcher::Launcher;
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt, SerialId};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::serial::level::gravity::SerialGravity;
+use crate::game::logic::PLAYER_LINEAR_DAMPING;
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+
+/// A launcher of the given radius that fires the player at the `launch_target` named by `to=`.
+///
+/// ```kdl
+/// launcher 0.75 to=ledge delay=0.5 time=2 frame=#true { pos 0 1 -20; }
+/// launch_target ledge { pos 0 6 -50; }
+/// ```
+///
+/// `delay` is how long the player is held, 0.5 seconds by default, and `time` how long they fly,
+/// 1.5 seconds by default. `frame=#true` turns the camera towards the target while waiting.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialLauncher {
+    pub to: SerialId,
+    pub radius: f32,
+    pub delay: f32,
+    pub flight_time: f32,
+    pub frame_camera: bool,
+    pub material: Handle<StandardMaterial>,
+    pub trans: Transform,
+}
+
+/// Where a [`SerialLauncher`] fires the player to.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialLaunchTarget {
+    pub id: SerialId,
+    pub trans: Transform,
+}
+
+impl SerialLauncher {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let to = node.must_get_id("to", &source);
+
+        let radius = node
+            .get_positive_number(0, &source)
+            .map(|radius| radius.unwrap_or(0.75) as f32);
+
+        let delay = node
+            .get_positive_number("delay", &source)
+            .map(|delay| delay.unwrap_or(0.5) as f32);
+
+        let flight_time = node
+            .get_positive_number("time", &source)
+            .map(|time| time.unwrap_or(1.5) as f32);
+
+        let frame_camera = node
+            .get_bool("frame", &source)
+            .map(|frame| frame.unwrap_or_default());
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| handle.unwrap_or_else(|| asset_ref::default_text_material(load_context)));
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let (to, radius, delay, flight_time, frame_camera, material, trans) = (
+            to,
+            radius,
+            delay,
+            flight_time,
+            frame_camera,
+            material,
+            trans,
+        )
+            .merge()?;
+
+        Ok(Self {
+            to,
+            radius,
+            delay,
+            flight_time,
+            frame_camera,
+            material,
+            trans,
+        })
+    }
+
+    /// The launcher firing at its target out of all of the level's launch `targets`.
+    pub fn launcher(&self, targets: &[SerialLaunchTarget]) -> Option<Launcher> {
+        let target = targets
+            .iter()
+            .find(|target| target.id.value == self.to.value)?;
+
+        Some(Launcher {
+            target: target.trans.translation,
+            delay: self.delay,
+            flight_time: self.flight_time,
+            frame_camera: self.frame_camera,
+        })
+    }
+
+    pub fn spawn(&self, targets: &[SerialLaunchTarget], args: &mut LevelBuildArgs) {
+        let Some(launcher) = self.launcher(targets) else {
+            return;
+        };
+
+        args.cmd.spawn((
+            launcher,
+            self.trans,
+            Mesh3d(args.assets.add(Sphere::new(self.radius).into())),
+            MeshMaterial3d(self.material.clone()),
+            Collider::sphere(self.radius),
+        ));
+    }
+}
+
+impl SerialLaunchTarget {
+    pub fn bind(
+        node: &KdlNode,
+        _load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let id = node.must_get_id(0, &source);
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let (id, trans) = (id, trans).merge()?;
+
+        Ok(Self { id, trans })
+    }
+}
+
+/// Checks that launch target ids are unique and that every launcher fires at one that exists.
+pub fn check_launch_target_ids(
+    launchers: &[SerialLauncher],
+    targets: &[SerialLaunchTarget],
+    source: &Arc<String>,
+) -> Result<(), KdlBindError> {
+    let ids = SerialId::unique(
+        "launch_target",
+        targets.iter().map(|target| &target.id),
+        source,
+    )?;
+
+    launchers
+        .iter()
+        .map(|launcher| launcher.to.must_refer_to("launch_target", &ids, source))
+        .collect::<Vec<_>>()
+        .merge()
+        .map(|_| ())
+}
+
+/// Checks that no launcher fires the player through a gravity zone, the launch velocity only
+/// accounts for the level's gravity.
+pub fn check_launch_arcs(
+    launchers: &[SerialLauncher],
+    targets: &[SerialLaunchTarget],
+    gravity: &SerialGravity,
+    source: &Arc<String>,
+) -> Result<(), KdlBindError> {
+    launchers
+        .iter()
+        .filter_map(|serial| Some((serial, serial.launcher(targets)?)))
+        .map(|(serial, launcher)| {
+            let mut arc = launcher.arc(
+                serial.trans.translation,
+                gravity.gravity,
+                PLAYER_LINEAR_DAMPING,
+            );
+            if arc.any(|point| gravity.zones.iter().any(|zone| zone.contains(point))) {
+                Err(source.err(
+                    format!(
+                        "Launcher to '{}' fires through a gravity zone",
+                        serial.to.value
+                    ),
+                    Some(serial.to.span()),
+                ))
+            } else {
+                Ok(())
+            }
+        })
+        .collect::<Vec<_>>()
+        .merge()
+        .map(|_| ())
+}
@@ -0,0 +1,80 @@
+use crate::game::levels::LevelObject;
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlEntryExt, KdlNodeExt, SerialId};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::signal::SignalVisibility;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+
+const DEFAULT_LIGHT_RANGE: f64 = 20.0;
+
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialLight {
+    /// Luminous power in lumens.
+    pub intensity: f32,
+    pub color: Color,
+    pub range: f32,
+    pub trans: Transform,
+    /// Signal that turns the light on, the light is always on without one.
+    pub when: Option<SerialId>,
+}
+
+impl SerialLight {
+    pub fn bind(
+        node: &KdlNode,
+        _load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let intensity = node.must_get_number(0, &source);
+
+        let color = node.entry("color").map_or(Ok(Color::WHITE), |entry| {
+            let hex = entry.as_string(&source)?;
+            Srgba::hex(hex)
+                .map(Color::from)
+                .map_err(|err| source.parse_error(err, entry.span()))
+        });
+
+        let range = node
+            .get_number("range", &source)
+            .map(|range| range.unwrap_or(DEFAULT_LIGHT_RANGE));
+
+        let when = node.get_id("when", &source);
+
+        let trans = node
+            .children()
+            .map_or(Ok(None), |doc| doc.get_transform(&source).map(Some))
+            .map(|trans| trans.unwrap_or_default());
+
+        let (intensity, color, range, when, trans) =
+            (intensity, color, range, when, trans).merge()?;
+
+        Ok(Self {
+            intensity: intensity as f32,
+            color,
+            range: range as f32,
+            trans,
+            when,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        let mut commands = args.cmd.spawn((
+            LevelObject,
+            self.trans,
+            PointLight {
+                intensity: self.intensity,
+                color: self.color,
+                range: self.range,
+                ..default()
+            },
+        ));
+        if let Some(when) = &self.when {
+            commands.insert(SignalVisibility {
+                signal: when.value.clone(),
+            });
+        }
+    }
+}
@@ -3,25 +3,69 @@ use crate::game::assets::fonts::FontNames;
 use crate::game::assets::preload::Preloads;
 use crate::game::levels::finish_point::FinishPoint;
 use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
-use crate::game::levels::serial::kdl_utils::KdlDocumentExt;
-use crate::game::levels::serial::level::button::{SerialButton, SerialButtonDoor};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::level::bounds::SerialBounds;
+use crate::game::levels::serial::level::button::{
+    SerialButton, SerialButtonDoor, check_button_ids, check_presser_groups,
+};
+use crate::game::levels::serial::level::checkpoint::SerialCheckpoint;
+use crate::game::levels::serial::level::crumble::{CRUMBLE_NODES, SerialCrumble};
 use crate::game::levels::serial::level::cuboid::SerialCuboid;
 use crate::game::levels::serial::level::dynamic::SerialDynamicObject;
+use crate::game::levels::serial::level::force::{FORCE_FIELD_NODES, SerialForceField};
+use crate::game::levels::serial::level::gem::SerialGems;
+use crate::game::levels::serial::level::gravity::SerialGravity;
+use crate::game::levels::serial::level::joint::{JointBodies, SerialJoints, check_joint_ids};
+use crate::game::levels::serial::level::key::{SerialKey, SerialLockedDoor, check_key_ids};
+use crate::game::levels::serial::level::launcher::{
+    SerialLaunchTarget, SerialLauncher, check_launch_arcs, check_launch_target_ids,
+};
+use crate::game::levels::serial::level::light::SerialLight;
+use crate::game::levels::serial::level::model::SerialModel;
+use crate::game::levels::serial::level::mover::SerialMover;
 use crate::game::levels::serial::level::music::{SerialMusic, SerialTriggeredMusic};
+use crate::game::levels::serial::level::pad::SerialPad;
 use crate::game::levels::serial::level::plane::SerialPlane;
+use crate::game::levels::serial::level::powerup::SerialPowerUp;
+use crate::game::levels::serial::level::shape::{SHAPE_NODES, SerialShape};
+use crate::game::levels::serial::level::signal::SerialSignals;
+use crate::game::levels::serial::level::spawner::SerialSpawner;
+use crate::game::levels::serial::level::teleporter::{SerialTeleporter, check_teleporter_ids};
 use crate::game::levels::serial::level::text::SerialText;
+use crate::game::levels::surface::Surface;
 use crate::game::levels::{LevelObject, PlayerSpawnPoint};
+use crate::game::records::ParTimes;
 use bevy::asset::LoadContext;
 use bevy::prelude::*;
 use kdl::KdlDocument;
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+mod bounds;
 mod button;
+mod checkpoint;
+mod crumble;
 mod cuboid;
-mod dynamic;
+pub mod dynamic;
+mod force;
+mod gem;
+mod gravity;
+mod joint;
+mod key;
+mod launcher;
+mod light;
+mod model;
+mod mover;
 mod music;
-mod plane;
+mod pad;
+pub mod plane;
+mod powerup;
+mod shape;
+mod signal;
+mod spawner;
+mod surface;
+mod teleporter;
 mod text;
 
 pub const DEFAULT_TEXT_PT: f64 = 64.0;
@@ -33,21 +77,45 @@ pub struct LevelBuildArgs<'a, 'w, 's> {
     pub assets: &'a AssetServer,
     pub preloads: &'a Preloads,
     pub fonts: &'a FontNames,
+    pub surfaces: &'a Assets<Surface>,
 }
 
 #[derive(Debug, Clone, Asset, Reflect)]
 #[reflect(Debug, Clone)]
 pub struct SerialLevel {
+    /// SHA-256 of the level source, used to match replays to the level they were recorded on.
+    pub hash: [u8; 32],
     pub spawn: Transform,
     pub finish: Transform,
+    pub times: ParTimes,
     pub default_music: Option<SerialMusic>,
     pub triggered_music: Vec<SerialTriggeredMusic>,
     pub planes: Vec<SerialPlane>,
     pub cuboids: Vec<SerialCuboid>,
+    pub shapes: Vec<SerialShape>,
+    pub models: Vec<SerialModel>,
+    pub crumbles: Vec<SerialCrumble>,
     pub texts: Vec<SerialText>,
     pub buttons: Vec<SerialButton>,
     pub button_doors: Vec<SerialButtonDoor>,
+    pub keys: Vec<SerialKey>,
+    pub locked_doors: Vec<SerialLockedDoor>,
     pub dynamic_objects: Vec<SerialDynamicObject>,
+    pub joints: SerialJoints,
+    pub spawners: Vec<SerialSpawner>,
+    pub checkpoints: Vec<SerialCheckpoint>,
+    pub lights: Vec<SerialLight>,
+    pub movers: Vec<SerialMover>,
+    pub pads: Vec<SerialPad>,
+    pub gravity: SerialGravity,
+    pub bounds: SerialBounds,
+    pub force_fields: Vec<SerialForceField>,
+    pub teleporters: Vec<SerialTeleporter>,
+    pub launchers: Vec<SerialLauncher>,
+    pub launch_targets: Vec<SerialLaunchTarget>,
+    pub gems: SerialGems,
+    pub power_ups: Vec<SerialPowerUp>,
+    pub signals: SerialSignals,
 }
 
 impl SerialLevel {
@@ -56,6 +124,8 @@ impl SerialLevel {
         load_context: &mut LoadContext,
         source: Arc<String>,
     ) -> Result<Self, KdlBindError> {
+        let hash = Sha256::digest(source.as_bytes()).0;
+
         let spawn = capture_result! {
             let doc = doc.must_children("spawn", &source)?;
             doc.get_transform(&source)
@@ -66,6 +136,16 @@ impl SerialLevel {
             doc.get_transform(&source)
         };
 
+        let times = doc.get("times").map_or(Ok(ParTimes::default()), |node| {
+            let par = node.get_positive_number("par", &source);
+            let gold = node.get_positive_number("gold", &source);
+            let (par, gold) = (par, gold).merge()?;
+            Ok(ParTimes {
+                par: par.map(|par| par as f32),
+                gold: gold.map(|gold| gold as f32),
+            })
+        });
+
         let default_music = doc
             .nodes()
             .iter()
@@ -98,6 +178,30 @@ impl SerialLevel {
             .collect::<Vec<_>>()
             .merge();
 
+        let shapes = doc
+            .nodes()
+            .iter()
+            .filter(|node| SHAPE_NODES.contains(&node.name().value()))
+            .map(|node| SerialShape::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let models = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "model")
+            .map(|node| SerialModel::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let crumbles = doc
+            .nodes()
+            .iter()
+            .filter(|node| CRUMBLE_NODES.contains(&node.name().value()))
+            .map(|node| SerialCrumble::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
         let texts = doc
             .nodes()
             .iter()
@@ -122,6 +226,22 @@ impl SerialLevel {
             .collect::<Vec<_>>()
             .merge();
 
+        let keys = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "key")
+            .map(|node| SerialKey::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let locked_doors = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "locked_door")
+            .map(|node| SerialLockedDoor::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
         let dynamic_objects = doc
             .nodes()
             .iter()
@@ -130,43 +250,238 @@ impl SerialLevel {
             .collect::<Vec<_>>()
             .merge();
 
-        let (
-            spawn,
-            finish,
-            default_music,
-            triggered_music,
-            planes,
-            cuboids,
-            texts,
-            buttons,
-            button_doors,
-            dynamic_objects,
-        ) = (
+        let joints = SerialJoints::bind(doc, load_context, source.clone());
+
+        let spawners = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "spawner")
+            .map(|node| SerialSpawner::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let checkpoints = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "checkpoint")
+            .map(|node| SerialCheckpoint::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let lights = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "light")
+            .map(|node| SerialLight::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let movers = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "mover")
+            .map(|node| SerialMover::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let pads = doc
+            .nodes()
+            .iter()
+            .filter(|node| matches!(node.name().value(), "boost" | "bounce"))
+            .map(|node| SerialPad::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let gravity = SerialGravity::bind(doc, load_context, source.clone());
+
+        let bounds = SerialBounds::bind(doc, load_context, source.clone());
+
+        let force_fields = doc
+            .nodes()
+            .iter()
+            .filter(|node| FORCE_FIELD_NODES.contains(&node.name().value()))
+            .map(|node| SerialForceField::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let teleporters = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "teleporter")
+            .map(|node| SerialTeleporter::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let launchers = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "launcher")
+            .map(|node| SerialLauncher::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let launch_targets = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "launch_target")
+            .map(|node| SerialLaunchTarget::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let gems = SerialGems::bind(doc, load_context, source.clone());
+
+        let power_ups = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "powerup")
+            .map(|node| SerialPowerUp::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let signals = SerialSignals::bind(doc, load_context, source.clone());
+
+        // tuples only merge up to eleven results, so the parts of the level are merged by category
+        let layout = (
             spawn,
             finish,
+            times,
             default_music,
             triggered_music,
-            planes,
-            cuboids,
-            texts,
+            gravity,
+            bounds,
+            gems,
+            signals,
+        )
+            .merge();
+        let geometry = (planes, cuboids, shapes, models, crumbles, texts, lights).merge();
+        let objects = (
             buttons,
             button_doors,
+            keys,
+            locked_doors,
             dynamic_objects,
+            joints,
+            spawners,
+            checkpoints,
+            movers,
+        )
+            .merge();
+        let devices = (
+            pads,
+            force_fields,
+            teleporters,
+            launchers,
+            launch_targets,
+            power_ups,
         )
-            .merge()?;
+            .merge();
 
-        Ok(Self {
+        let (
+            (spawn, finish, times, default_music, triggered_music, gravity, bounds, gems, signals),
+            (planes, cuboids, shapes, models, crumbles, texts, lights),
+            (
+                buttons,
+                button_doors,
+                keys,
+                locked_doors,
+                dynamic_objects,
+                joints,
+                spawners,
+                checkpoints,
+                movers,
+            ),
+            (pads, force_fields, teleporters, launchers, launch_targets, power_ups),
+        ) = (layout, geometry, objects, devices).merge()?;
+
+        let level = Self {
+            hash,
             spawn,
             default_music,
             triggered_music,
             finish,
+            times,
             planes,
             cuboids,
+            shapes,
+            models,
+            crumbles,
             texts,
             buttons,
             button_doors,
+            keys,
+            locked_doors,
             dynamic_objects,
-        })
+            joints,
+            spawners,
+            checkpoints,
+            lights,
+            movers,
+            pads,
+            gravity,
+            bounds,
+            force_fields,
+            teleporters,
+            launchers,
+            launch_targets,
+            gems,
+            power_ups,
+            signals,
+        };
+        level.check_references(&source)?;
+        Ok(level)
+    }
+
+    /// Checks that everything referred to by id exists, so spawning can look references up without
+    /// handling missing ones.
+    fn check_references(&self, source: &Arc<String>) -> Result<(), KdlBindError> {
+        let buttons = check_button_ids(&self.buttons, &self.button_doors, source);
+
+        let emitted = self
+            .signals
+            .emitted()
+            .chain(
+                self.buttons
+                    .iter()
+                    .filter_map(|button| button.signal.as_ref()),
+            )
+            .map(|signal| signal.value.as_str())
+            .collect::<HashSet<_>>();
+        let signals = self
+            .button_doors
+            .iter()
+            .flat_map(|door| door.requires.signals())
+            .chain(self.texts.iter().filter_map(|text| text.when.as_ref()))
+            .chain(self.lights.iter().filter_map(|light| light.when.as_ref()))
+            .chain(self.movers.iter().filter_map(|mover| mover.start.as_ref()))
+            .chain(self.signals.actions.iter().map(|actions| &actions.signal))
+            .map(|signal| signal.must_refer_to("signal", &emitted, source))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let teleporters = check_teleporter_ids(&self.teleporters, source);
+
+        let launchers = check_launch_target_ids(&self.launchers, &self.launch_targets, source);
+
+        let arcs = check_launch_arcs(&self.launchers, &self.launch_targets, &self.gravity, source);
+
+        let joints = check_joint_ids(&self.dynamic_objects, &self.joints, source);
+
+        let keys = check_key_ids(&self.keys, &self.locked_doors, source);
+
+        let groups = check_presser_groups(&self.buttons, &self.dynamic_objects, source);
+
+        (
+            buttons,
+            signals,
+            teleporters,
+            launchers,
+            arcs,
+            joints,
+            keys,
+            groups,
+        )
+            .merge()
+            .map(|_| ())
     }
 
     pub fn spawn(&self, args: &mut LevelBuildArgs) {
@@ -189,21 +504,98 @@ impl SerialLevel {
             cuboid.spawn(args);
         }
 
+        for shape in self.shapes.iter() {
+            shape.spawn(args);
+        }
+
+        for model in self.models.iter() {
+            model.spawn(args);
+        }
+
+        for crumble in self.crumbles.iter() {
+            crumble.spawn(args);
+        }
+
         for text in self.texts.iter() {
             text.spawn(args);
         }
 
-        let mut button_names = HashMap::new();
-        for button in self.buttons.iter() {
-            button_names.insert(button.name.clone(), button.spawn(args));
+        // buttons and doors keep their state across checkpoint restarts
+        if args.dyn_assets {
+            let mut buttons = HashMap::new();
+            for button in self.buttons.iter() {
+                buttons.insert(button.id.value.clone(), button.spawn(args));
+            }
+
+            for button_door in self.button_doors.iter() {
+                button_door.spawn(&buttons, args);
+            }
+
+            for locked_door in self.locked_doors.iter() {
+                locked_door.spawn(args);
+            }
         }
 
-        for button_door in self.button_doors.iter() {
-            button_door.spawn(&button_names, args);
+        for key in self.keys.iter() {
+            key.spawn(args);
         }
 
+        let mut bodies = JointBodies::new();
         for dynamic_object in self.dynamic_objects.iter() {
-            dynamic_object.spawn(args);
+            if let Some(entity) = dynamic_object.spawn(args)
+                && let Some(id) = &dynamic_object.id
+            {
+                bodies.insert(id.value.clone(), (entity, dynamic_object.trans));
+            }
+        }
+
+        self.joints.spawn(&bodies, args);
+
+        for spawner in self.spawners.iter() {
+            spawner.spawn(args);
+        }
+
+        for checkpoint in self.checkpoints.iter() {
+            checkpoint.spawn(args);
         }
+
+        for light in self.lights.iter() {
+            light.spawn(args);
+        }
+
+        // movers keep going from where they are when restarting from a checkpoint
+        if args.dyn_assets {
+            for mover in self.movers.iter() {
+                mover.spawn(args);
+            }
+        }
+
+        for pad in self.pads.iter() {
+            pad.spawn(args);
+        }
+
+        self.gravity.spawn(args);
+
+        self.bounds.spawn(args);
+
+        for force_field in self.force_fields.iter() {
+            force_field.spawn(args);
+        }
+
+        for teleporter in self.teleporters.iter() {
+            teleporter.spawn(&self.teleporters, args);
+        }
+
+        for launcher in self.launchers.iter() {
+            launcher.spawn(&self.launch_targets, args);
+        }
+
+        self.gems.spawn(args);
+
+        for power_up in self.power_ups.iter() {
+            power_up.spawn(args);
+        }
+
+        self.signals.spawn(args);
     }
 }
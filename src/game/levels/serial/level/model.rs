@@ -0,0 +1,99 @@
+use crate::game::levels::DynamicLevelObject;
+use crate::game::levels::button::ButtonPresser;
+use crate::game::levels::gravity::GravityUp;
+use crate::game::levels::model::{LevelModel, ModelBody, ModelCollider, add_model_colliders};
+use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+use strum::VariantArray;
+
+/// A glTF scene with colliders made from its meshes.
+///
+/// ```kdl
+/// model "railing.glb#Scene0" { pos 0 0 -10; }
+/// model "crate.glb#Scene0" body=dynamic collider=convex_hull {
+///     pos 2 1 -10
+///     scale 0.5
+/// }
+/// ```
+///
+/// Dynamic models default to convex hull colliders, everything else to trimesh colliders.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialModel {
+    pub scene: Handle<Scene>,
+    pub collider: ModelCollider,
+    pub body: ModelBody,
+    pub trans: Transform,
+}
+
+impl SerialModel {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let scene = node.must_get_handle(0, load_context, &source);
+
+        let collider = node.get_variant("collider", ModelCollider::VARIANTS, &source);
+
+        let body = node
+            .get_variant("body", ModelBody::VARIANTS, &source)
+            .map(|body| body.copied().unwrap_or_default());
+
+        let trans = node
+            .children()
+            .map_or(Ok(None), |children| {
+                children.get_transform(&source).map(Some)
+            })
+            .map(|trans| trans.unwrap_or_default());
+
+        let (scene, collider, body, trans) = (scene, collider, body, trans).merge()?;
+
+        let collider = collider.copied().unwrap_or(match body {
+            ModelBody::Dynamic => ModelCollider::ConvexHull,
+            ModelBody::Static | ModelBody::Kinematic => ModelCollider::Trimesh,
+        });
+
+        Ok(Self {
+            scene,
+            collider,
+            body,
+            trans,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        let model = (
+            LevelModel {
+                collider: self.collider,
+            },
+            self.trans,
+            SceneRoot(self.scene.clone()),
+            self.body.rigid_body(),
+        );
+
+        match self.body {
+            // dynamic models stay where they are when restarting from a checkpoint
+            ModelBody::Dynamic => {
+                if args.dyn_assets {
+                    args.cmd
+                        .spawn((
+                            model,
+                            DynamicLevelObject,
+                            ButtonPresser,
+                            GravityUp::default(),
+                        ))
+                        .observe(add_model_colliders);
+                }
+            }
+            ModelBody::Static | ModelBody::Kinematic => {
+                args.cmd.spawn(model).observe(add_model_colliders);
+            }
+        }
+    }
+}
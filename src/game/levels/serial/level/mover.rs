@@ -0,0 +1,129 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::mover::{LevelMover, MoverEase, MoverMode, MoverWaypoint};
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt, SerialId};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+use strum::VariantArray;
+
+const DEFAULT_SEGMENT_DURATION: f64 = 1.0;
+
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialMover {
+    pub dimensions: Vec3,
+    pub material: Handle<StandardMaterial>,
+    pub mode: MoverMode,
+    pub start: Option<SerialId>,
+    pub waypoints: Vec<MoverWaypoint>,
+}
+
+impl SerialMover {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let dimensions = node.must_get_scale(0, &source);
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| {
+                handle.unwrap_or_else(|| asset_ref::default_plane_material(load_context))
+            });
+
+        let mode = node
+            .get_variant("mode", MoverMode::VARIANTS, &source)
+            .map(|mode| mode.copied().unwrap_or_default());
+
+        let ease = node
+            .get_variant("ease", MoverEase::VARIANTS, &source)
+            .map(|ease| ease.copied());
+
+        let start = node.get_id("start", &source);
+
+        let points = node.must_children(&source).and_then(|doc| {
+            doc.nodes()
+                .iter()
+                .filter(|point| point.name().value() == "point")
+                .map(|point| bind_point(point, &source))
+                .collect::<Vec<_>>()
+                .merge()
+        });
+
+        let (dimensions, material, mode, ease, start, points) =
+            (dimensions, material, mode, ease, start, points).merge()?;
+
+        if points.len() < 2 {
+            return Err(source.err(
+                "Mover needs at least two points".to_string(),
+                Some(node.span()),
+            ));
+        }
+
+        // points without their own easing use the mover's
+        let waypoints = points
+            .into_iter()
+            .map(|(waypoint, point_ease)| MoverWaypoint {
+                ease: point_ease.or(ease).unwrap_or_default(),
+                ..waypoint
+            })
+            .collect();
+
+        Ok(Self {
+            dimensions,
+            material,
+            mode,
+            start,
+            waypoints,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        args.cmd.spawn((
+            LevelMover {
+                waypoints: self.waypoints.clone(),
+                mode: self.mode,
+                start: self.start.as_ref().map(|start| start.value.clone()),
+                started: false,
+                elapsed: 0.0,
+            },
+            self.waypoints[0].trans,
+            Mesh3d(args.assets.add(Cuboid::from_size(self.dimensions).into())),
+            MeshMaterial3d(self.material.clone()),
+            Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z),
+        ));
+    }
+}
+
+fn bind_point(
+    point: &KdlNode,
+    source: &Arc<String>,
+) -> Result<(MoverWaypoint, Option<MoverEase>), KdlBindError> {
+    let duration = point
+        .get_positive_number(0, source)
+        .map(|duration| duration.unwrap_or(DEFAULT_SEGMENT_DURATION));
+
+    let ease = point
+        .get_variant("ease", MoverEase::VARIANTS, source)
+        .map(|ease| ease.copied());
+
+    let trans = point
+        .must_children(source)
+        .and_then(|doc| doc.get_transform(source));
+
+    let (duration, ease, trans) = (duration, ease, trans).merge()?;
+
+    Ok((
+        MoverWaypoint {
+            trans,
+            duration: duration as f32,
+            ease: MoverEase::default(),
+        },
+        ease,
+    ))
+}
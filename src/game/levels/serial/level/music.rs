@@ -2,7 +2,8 @@ use crate::game::levels::LevelObject;
 use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
 use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
 use crate::game::levels::serial::level::LevelBuildArgs;
-use crate::game::music::{BackgroundMusic, BackgroundMusicTrigger, PLAYBACK_SETTINGS};
+use crate::game::levels::signal::{SignalAction, SignalActions, SignalVolume};
+use crate::game::music::{BackgroundMusic, PLAYBACK_SETTINGS};
 use avian3d::prelude::*;
 use bevy::asset::LoadContext;
 use bevy::prelude::*;
@@ -68,10 +69,9 @@ impl SerialTriggeredMusic {
 
     pub fn spawn(&self, args: &mut LevelBuildArgs) {
         args.cmd.spawn((
-            LevelObject,
+            SignalVolume::default(),
+            SignalActions::local(vec![SignalAction::Music(self.audio.clone())]),
             self.trans,
-            BackgroundMusicTrigger(self.audio.clone()),
-            Sensor,
             Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z),
         ));
     }
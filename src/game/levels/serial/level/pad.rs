@@ -0,0 +1,106 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::pad::{LevelPad, PAD_REACH, PAD_SIZE, PadKind};
+use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+
+const DEFAULT_PAD_COOLDOWN: f64 = 0.5;
+const PAD_THICKNESS: f32 = 0.04;
+
+/// A `boost <speed>` or `bounce <speed>` pad.
+///
+/// Boosts push along their `direction`, which is relative to the pad and defaults to its forward
+/// direction.
+///
+/// ```kdl
+/// boost 8 cooldown=1 sound="preload:button-on" {
+///     pos 0 0 -4
+///     direction 0 0.2 -1
+/// }
+/// ```
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialPad {
+    pub kind: PadKind,
+    pub cooldown: f32,
+    pub sound: Option<Handle<AudioSource>>,
+    pub material: Handle<StandardMaterial>,
+    pub trans: Transform,
+}
+
+impl SerialPad {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let speed = node.must_get_positive_number(0, &source);
+
+        let cooldown = node
+            .get_positive_number("cooldown", &source)
+            .map(|cooldown| cooldown.unwrap_or(DEFAULT_PAD_COOLDOWN));
+
+        let sound = node.get_handle("sound", load_context, &source);
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| {
+                handle.unwrap_or_else(|| asset_ref::default_plane_material(load_context))
+            });
+
+        let trans = node
+            .children()
+            .map_or(Ok(None), |doc| doc.get_transform(&source).map(Some))
+            .map(|trans| trans.unwrap_or_default());
+
+        let direction = node
+            .children()
+            .and_then(|doc| doc.get("direction"))
+            .map_or(Ok(Dir3::NEG_Z), |direction| {
+                direction.must_get_direction(0, &source)
+            });
+
+        let (speed, cooldown, sound, material, trans, direction) =
+            (speed, cooldown, sound, material, trans, direction).merge()?;
+
+        let kind = match node.name().value() {
+            "bounce" => PadKind::Bounce(speed as f32),
+            _ => PadKind::Boost(trans.rotation * direction * speed as f32),
+        };
+
+        Ok(Self {
+            kind,
+            cooldown: cooldown as f32,
+            sound,
+            material,
+            trans,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        args.cmd.spawn((
+            LevelPad {
+                kind: self.kind,
+                cooldown: self.cooldown,
+                remaining: 0.0,
+                sound: self.sound.clone(),
+            },
+            self.trans,
+            Mesh3d(
+                args.assets
+                    .add(Cuboid::new(PAD_SIZE, PAD_THICKNESS, PAD_SIZE).into()),
+            ),
+            MeshMaterial3d(self.material.clone()),
+            Collider::compound(vec![(
+                Vec3::Y * PAD_REACH / 2.0,
+                Quat::IDENTITY,
+                Collider::cuboid(PAD_SIZE, PAD_REACH, PAD_SIZE),
+            )]),
+        ));
+    }
+}
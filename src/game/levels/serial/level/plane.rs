@@ -4,6 +4,7 @@ use crate::game::levels::death::DeathCollider;
 use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
 use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
 use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::serial::level::surface::SerialSurface;
 use avian3d::prelude::*;
 use bevy::asset::LoadContext;
 use bevy::prelude::*;
@@ -19,6 +20,7 @@ pub struct SerialPlane {
     pub trans: Transform,
     pub material: Handle<StandardMaterial>,
     pub ty: SerialPlaneType,
+    pub surface: SerialSurface,
 }
 
 #[derive(Debug, Default, Copy, Clone, Reflect, strum::VariantArray, strum::Display)]
@@ -54,7 +56,12 @@ impl SerialPlane {
             .map_or(Ok(None), |doc| doc.get_transform(&source).map(Some))
             .map(|trans| trans.unwrap_or_default());
 
-        let (size, size2, ty, material, trans) = (size, size2, ty, material, trans).merge()?;
+        let surface = SerialSurface::bind(node, load_context, &source);
+
+        let (size, size2, ty, material, trans, surface) =
+            (size, size2, ty, material, trans, surface).merge()?;
+
+        let surface = surface.with_material(&material, load_context);
 
         Ok(SerialPlane {
             width: size as f32,
@@ -62,12 +69,14 @@ impl SerialPlane {
             trans,
             material,
             ty,
+            surface,
         })
     }
 
     pub fn spawn(&self, args: &mut LevelBuildArgs) {
         match self.ty {
             SerialPlaneType::Static => {
+                let surface = self.surface.resolve(args).physics();
                 args.cmd.spawn((
                     LevelObject,
                     self.trans,
@@ -81,7 +90,8 @@ impl SerialPlane {
                     children![(
                         RigidBody::Static,
                         Collider::cuboid(self.width, 0.2, self.length),
-                        Transform::from_xyz(0.0, -0.1, 0.0)
+                        Transform::from_xyz(0.0, -0.1, 0.0),
+                        surface,
                     )],
                 ));
             }
@@ -0,0 +1,98 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::powerup::{PowerUp, PowerUpKind, PowerUpPickup};
+use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+use strum::VariantArray;
+
+/// A power-up pickup. The kind sets the default effects, which can be changed with properties.
+///
+/// ```kdl
+/// powerup super_jump { pos 0 1 -5; }
+/// powerup slow_fall duration=8 gravity=0.1 { pos 4 1 -10; }
+/// powerup super_speed scene="preload:physball" { pos -4 1 -10; }
+/// ```
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialPowerUp {
+    pub power_up: PowerUp,
+    pub trans: Transform,
+    /// Shown instead of the default pickup mesh.
+    pub scene: Option<Handle<Scene>>,
+    pub material: Handle<StandardMaterial>,
+}
+
+impl SerialPowerUp {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let kind = node.must_get_variant(0, PowerUpKind::VARIANTS, &source);
+
+        let duration = node.get_positive_number("duration", &source);
+        let jump = node.get_positive_number("jump", &source);
+        let acceleration = node.get_positive_number("acceleration", &source);
+        let bounce = node.get_positive_number("bounce", &source);
+        let gravity = node.get_positive_number("gravity", &source);
+
+        let scene = node.get_handle("scene", load_context, &source);
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| handle.unwrap_or_else(|| asset_ref::default_text_material(load_context)));
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let (kind, duration, jump, acceleration, bounce, gravity, scene, material, trans) = (
+            kind,
+            duration,
+            jump,
+            acceleration,
+            bounce,
+            gravity,
+            scene,
+            material,
+            trans,
+        )
+            .merge()?;
+
+        let defaults = kind.power_up();
+        let power_up = PowerUp {
+            kind: *kind,
+            duration: duration.map_or(defaults.duration, |duration| duration as f32),
+            jump: jump.map_or(defaults.jump, |jump| jump as f32),
+            acceleration: acceleration
+                .map_or(defaults.acceleration, |acceleration| acceleration as f32),
+            bounce: bounce.map(|bounce| bounce as f32).or(defaults.bounce),
+            gravity: gravity.map_or(defaults.gravity, |gravity| gravity as f32),
+        };
+
+        Ok(Self {
+            power_up,
+            trans,
+            scene,
+            material,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        let mut pickup = args
+            .cmd
+            .spawn((PowerUpPickup::new(self.power_up), self.trans));
+        if let Some(scene) = &self.scene {
+            pickup.insert(SceneRoot(scene.clone()));
+        } else {
+            pickup.insert((
+                Mesh3d(args.assets.add(Cuboid::from_length(0.3).into())),
+                MeshMaterial3d(self.material.clone()),
+            ));
+        }
+    }
+}
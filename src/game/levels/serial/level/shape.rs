@@ -0,0 +1,412 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::LevelObject;
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlEntryExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::serial::level::surface::SerialSurface;
+use avian3d::prelude::*;
+use bevy::asset::{LoadContext, RenderAssetUsages};
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+
+/// Nodes that bind to a [`SerialShape`].
+pub const SHAPE_NODES: [&str; 7] = [
+    "wedge", "ramp", "cylinder", "sphere", "capsule", "torus", "curve",
+];
+
+/// Curves get a segment every this many degrees.
+const CURVE_SEGMENT_ANGLE: f32 = 5.0;
+
+/// Static level geometry other than planes and cuboids.
+///
+/// ```kdl
+/// // width, height and length, rising towards -z
+/// ramp 4 1 6 { pos 0 0.5 -8; }
+/// cylinder 1 3 { pos 4 1.5 -8; }
+/// sphere 2 { pos -4 0 -8; }
+/// // radius and length between the cap centers
+/// capsule 0.5 4 { pos 0 0.5 -14; rot z 90; }
+/// // radius and tube radius
+/// torus 6 0.5 { pos 0 0 -24; }
+/// // radius, angle and width, turning left for positive angles
+/// curve 10 90 4 bank=15 rise=2 thickness=0.2 { pos 0 0 -30; }
+/// ```
+///
+/// Curves start at their position heading towards -z, with the top of the track at the position.
+/// A positive `bank` raises the outer edge.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialShape {
+    pub shape: ShapeKind,
+    pub material: Handle<StandardMaterial>,
+    pub trans: Transform,
+    pub surface: SerialSurface,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum ShapeKind {
+    Wedge {
+        dimensions: Vec3,
+    },
+    Cylinder {
+        radius: f32,
+        height: f32,
+    },
+    Sphere {
+        radius: f32,
+    },
+    Capsule {
+        radius: f32,
+        length: f32,
+    },
+    Torus {
+        radius: f32,
+        tube: f32,
+    },
+    Curve {
+        radius: f32,
+        /// In radians, positive turns left.
+        angle: f32,
+        width: f32,
+        /// In radians, positive raises the outer edge.
+        bank: f32,
+        rise: f32,
+        thickness: f32,
+    },
+}
+
+impl SerialShape {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let shape = ShapeKind::bind(node, &source);
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| {
+                handle.unwrap_or_else(|| asset_ref::default_plane_material(load_context))
+            });
+
+        let trans = node
+            .children()
+            .map_or(Ok(None), |doc| doc.get_transform(&source).map(Some))
+            .map(|trans| trans.unwrap_or_default());
+
+        let surface = SerialSurface::bind(node, load_context, &source);
+
+        let (shape, material, trans, surface) = (shape, material, trans, surface).merge()?;
+
+        let surface = surface.with_material(&material, load_context);
+
+        Ok(Self {
+            shape,
+            material,
+            trans,
+            surface,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        let surface = self.surface.resolve(args).physics();
+        let mesh = self.shape.to_mesh();
+        let collider = self.shape.to_collider(&mesh);
+        args.cmd.spawn((
+            LevelObject,
+            self.trans,
+            Mesh3d(args.assets.add(mesh)),
+            MeshMaterial3d(self.material.clone()),
+            RigidBody::Static,
+            collider,
+            surface,
+        ));
+    }
+}
+
+impl ShapeKind {
+    fn bind(node: &KdlNode, source: &Arc<String>) -> Result<Self, KdlBindError> {
+        let size = |i: usize| node.must_get_positive_number(i, source).map(|n| n as f32);
+
+        match node.name().value() {
+            "wedge" | "ramp" => {
+                let (x, y, z) = (size(0), size(1), size(2)).merge()?;
+                Ok(ShapeKind::Wedge {
+                    dimensions: Vec3::new(x, y, z),
+                })
+            }
+            "cylinder" => {
+                let (radius, height) = (size(0), size(1)).merge()?;
+                Ok(ShapeKind::Cylinder { radius, height })
+            }
+            "sphere" => size(0).map(|radius| ShapeKind::Sphere { radius }),
+            "capsule" => {
+                let (radius, length) = (size(0), size(1)).merge()?;
+                Ok(ShapeKind::Capsule { radius, length })
+            }
+            "torus" => {
+                let (radius, tube) = (size(0), size(1)).merge()?;
+                Ok(ShapeKind::Torus { radius, tube })
+            }
+            _ => {
+                let angle = node.must_entry(1, source).and_then(|entry| {
+                    let angle = entry.as_number(source)?;
+                    if angle != 0.0 && (-360.0..=360.0).contains(&angle) {
+                        Ok(angle as f32)
+                    } else {
+                        Err(source.out_of_range(angle, -360, 360, entry.span()))
+                    }
+                });
+                let bank = node.get_number("bank", source);
+                let rise = node.get_number("rise", source);
+                let thickness = node.get_positive_number("thickness", source);
+
+                let (radius, angle, width, bank, rise, thickness) =
+                    (size(0), angle, size(2), bank, rise, thickness).merge()?;
+
+                Ok(ShapeKind::Curve {
+                    radius,
+                    angle: angle.to_radians(),
+                    width,
+                    bank: (bank.unwrap_or_default() as f32).to_radians(),
+                    rise: rise.unwrap_or_default() as f32,
+                    thickness: thickness.unwrap_or(0.2) as f32,
+                })
+            }
+        }
+    }
+
+    pub fn to_mesh(self) -> Mesh {
+        match self {
+            ShapeKind::Wedge { dimensions } => {
+                let mut mesh = ShapeMesh::default();
+                let [a, b, c, d, e, f] = wedge_corners(dimensions);
+                mesh.polygon(&[a, b, c, d], Vec3::NEG_Y);
+                mesh.polygon(&[c, d, e, f], Vec3::NEG_Z);
+                mesh.polygon(&[a, b, f, e], Vec3::new(0.0, dimensions.z, dimensions.y));
+                mesh.polygon(&[a, d, e], Vec3::NEG_X);
+                mesh.polygon(&[b, c, f], Vec3::X);
+                mesh.into()
+            }
+            ShapeKind::Cylinder { radius, height } => Cylinder::new(radius, height).into(),
+            ShapeKind::Sphere { radius } => Sphere::new(radius).into(),
+            ShapeKind::Capsule { radius, length } => Capsule3d::new(radius, length).into(),
+            ShapeKind::Torus { radius, tube } => Torus {
+                minor_radius: tube,
+                major_radius: radius,
+            }
+            .into(),
+            ShapeKind::Curve {
+                radius,
+                angle,
+                width,
+                bank,
+                rise,
+                thickness,
+            } => curve_mesh(radius, angle, width, bank, rise, thickness).into(),
+        }
+    }
+
+    /// The collider matching [`ShapeKind::to_mesh`].
+    pub fn to_collider(self, mesh: &Mesh) -> Collider {
+        match self {
+            ShapeKind::Wedge { dimensions } => {
+                Collider::convex_hull(wedge_corners(dimensions).to_vec())
+                    .expect("wedge with positive dimensions")
+            }
+            ShapeKind::Cylinder { radius, height } => Collider::cylinder(radius, height),
+            ShapeKind::Sphere { radius } => Collider::sphere(radius),
+            ShapeKind::Capsule { radius, length } => Collider::capsule(radius, length),
+            ShapeKind::Torus { .. } | ShapeKind::Curve { .. } => {
+                Collider::trimesh_from_mesh(mesh).expect("generated mesh with indices")
+            }
+        }
+    }
+}
+
+/// Bottom front left, bottom front right, bottom back right, bottom back left, top back left and
+/// top back right.
+fn wedge_corners(dimensions: Vec3) -> [Vec3; 6] {
+    let Vec3 { x, y, z } = dimensions / 2.0;
+    [
+        Vec3::new(-x, -y, z),
+        Vec3::new(x, -y, z),
+        Vec3::new(x, -y, -z),
+        Vec3::new(-x, -y, -z),
+        Vec3::new(-x, y, -z),
+        Vec3::new(x, y, -z),
+    ]
+}
+
+/// Position and basis of a curve's centerline after turning `t` of the way.
+struct CurveFrame {
+    center: Vec3,
+    tangent: Vec3,
+    /// Across the track, towards the outer edge.
+    across: Vec3,
+    up: Vec3,
+}
+
+fn curve_frame(radius: f32, angle: f32, bank: f32, rise: f32, t: f32) -> CurveFrame {
+    let side = angle.signum();
+    let (sin, cos) = (angle.abs() * t).sin_cos();
+
+    let center = Vec3::new(side * radius * (cos - 1.0), rise * t, -radius * sin);
+    let tangent = Vec3::new(-side * radius * sin, rise / angle.abs(), -radius * cos).normalize();
+    let outward = Vec3::new(side * cos, 0.0, -sin);
+    let (bank_sin, bank_cos) = bank.sin_cos();
+
+    CurveFrame {
+        center,
+        tangent,
+        across: outward * bank_cos + Vec3::Y * bank_sin,
+        up: Vec3::Y * bank_cos - outward * bank_sin,
+    }
+}
+
+fn curve_mesh(
+    radius: f32,
+    angle: f32,
+    width: f32,
+    bank: f32,
+    rise: f32,
+    thickness: f32,
+) -> ShapeMesh {
+    let segments = (angle.abs() / CURVE_SEGMENT_ANGLE.to_radians())
+        .ceil()
+        .max(1.0) as usize;
+    let frames = (0..=segments)
+        .map(|i| curve_frame(radius, angle, bank, rise, i as f32 / segments as f32))
+        .collect::<Vec<_>>();
+
+    // top inner, top outer, bottom outer and bottom inner, going around the cross section
+    let corners = |frame: &CurveFrame| {
+        let half_across = frame.across * width / 2.0;
+        let depth = frame.up * thickness;
+        [
+            frame.center - half_across,
+            frame.center + half_across,
+            frame.center + half_across - depth,
+            frame.center - half_across - depth,
+        ]
+    };
+    let length = radius * angle.abs() / width;
+
+    let mut mesh = ShapeMesh::default();
+    for side in 0..4 {
+        let normal = |frame: &CurveFrame| match side {
+            0 => frame.up,
+            1 => frame.across,
+            2 => -frame.up,
+            _ => -frame.across,
+        };
+        let rings = frames
+            .iter()
+            .map(|frame| {
+                let corners = corners(frame);
+                (corners[side], corners[(side + 1) % 4], normal(frame))
+            })
+            .collect::<Vec<_>>();
+        mesh.strip(&rings, length);
+    }
+
+    let (first, last) = (&frames[0], &frames[segments]);
+    mesh.polygon(&corners(first), -first.tangent);
+    mesh.polygon(&corners(last), last.tangent);
+    mesh
+}
+
+/// Builds triangle meshes from faces, winding each triangle to face its normal.
+#[derive(Default)]
+struct ShapeMesh {
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<Vec2>,
+    indices: Vec<u32>,
+}
+
+impl ShapeMesh {
+    fn vertex(&mut self, position: Vec3, normal: Vec3, uv: Vec2) -> u32 {
+        self.positions.push(position);
+        self.normals.push(normal.normalize());
+        self.uvs.push(uv);
+        self.positions.len() as u32 - 1
+    }
+
+    fn triangle(&mut self, a: u32, b: u32, c: u32) {
+        let [pa, pb, pc] = [a, b, c].map(|i| self.positions[i as usize]);
+        if (pb - pa).cross(pc - pa).dot(self.normals[a as usize]) >= 0.0 {
+            self.indices.extend([a, b, c]);
+        } else {
+            self.indices.extend([a, c, b]);
+        }
+    }
+
+    /// A flat convex polygon, with the texture projected onto it.
+    fn polygon(&mut self, corners: &[Vec3], normal: Vec3) {
+        let (u, v) = normal.normalize().any_orthonormal_pair();
+        let first = self.positions.len() as u32;
+        for &corner in corners {
+            self.vertex(corner, normal, Vec2::new(corner.dot(u), corner.dot(v)));
+        }
+        for i in 1..corners.len() as u32 - 1 {
+            self.triangle(first, first + i, first + i + 1);
+        }
+    }
+
+    /// A smooth band through pairs of edge points, textured along its `length`.
+    fn strip(&mut self, rings: &[(Vec3, Vec3, Vec3)], length: f32) {
+        let last = rings.len() - 1;
+        let first = self.positions.len() as u32;
+        for (i, &(a, b, normal)) in rings.iter().enumerate() {
+            let v = length * i as f32 / last as f32;
+            self.vertex(a, normal, Vec2::new(0.0, v));
+            self.vertex(b, normal, Vec2::new(1.0, v));
+        }
+        for i in 0..last as u32 {
+            let [a0, b0, a1, b1] = [0, 1, 2, 3].map(|j| first + i * 2 + j);
+            self.triangle(a0, b0, a1);
+            self.triangle(a1, b0, b1);
+        }
+    }
+}
+
+impl From<ShapeMesh> for Mesh {
+    fn from(mesh: ShapeMesh) -> Self {
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, mesh.positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, mesh.normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, mesh.uvs)
+        .with_inserted_indices(Indices::U32(mesh.indices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn test_curve_frame() {
+        let left = curve_frame(10.0, PI / 2.0, 0.0, 2.0, 1.0);
+        assert!(left.center.abs_diff_eq(Vec3::new(-10.0, 2.0, -10.0), 1e-4));
+        assert!(left.across.abs_diff_eq(Vec3::NEG_Z, 1e-4));
+
+        let right = curve_frame(10.0, -PI / 2.0, 0.0, 0.0, 1.0);
+        assert!(right.center.abs_diff_eq(Vec3::new(10.0, 0.0, -10.0), 1e-4));
+        assert!(right.tangent.abs_diff_eq(Vec3::X, 1e-4));
+
+        let banked = curve_frame(10.0, PI / 2.0, PI / 4.0, 0.0, 0.0);
+        assert!(
+            banked
+                .up
+                .abs_diff_eq(Vec3::new(-1.0, 1.0, 0.0).normalize(), 1e-4)
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt, SerialId};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::signal::{
+    SignalAction, SignalActions, SignalCollectible, SignalEmitter, SignalTimer, SignalVolume,
+};
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::{KdlDocument, KdlNode};
+use std::sync::Arc;
+
+/// Nodes that can go in an `on` block.
+const ACTION_NODES: [&str; 3] = ["music", "kill", "finish"];
+
+/// Signal sensors and actions in a level, see [`crate::game::levels::signal`].
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialSignals {
+    pub volumes: Vec<SerialVolume>,
+    pub timers: Vec<SerialSignalTimer>,
+    pub collectibles: Vec<SerialCollectible>,
+    pub actions: Vec<SerialSignalActions>,
+}
+
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialVolume {
+    pub signal: SerialId,
+    pub dimensions: Vec3,
+    pub trans: Transform,
+}
+
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialSignalTimer {
+    pub signal: SerialId,
+    pub timer: SignalTimer,
+}
+
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialCollectible {
+    pub signal: SerialId,
+    pub trans: Transform,
+    pub material: Handle<StandardMaterial>,
+}
+
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialSignalActions {
+    pub signal: SerialId,
+    pub actions: Vec<SignalAction>,
+}
+
+impl SerialSignals {
+    pub fn bind(
+        doc: &KdlDocument,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let volumes = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "volume")
+            .map(|node| SerialVolume::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let timers = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "timer")
+            .map(|node| SerialSignalTimer::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let collectibles = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "collectible")
+            .map(|node| SerialCollectible::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let actions = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "on")
+            .map(|node| SerialSignalActions::bind(node, load_context, source.clone()))
+            .collect::<Vec<_>>()
+            .merge();
+
+        let (volumes, timers, collectibles, actions) =
+            (volumes, timers, collectibles, actions).merge()?;
+
+        Ok(Self {
+            volumes,
+            timers,
+            collectibles,
+            actions,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        for volume in self.volumes.iter() {
+            volume.spawn(args);
+        }
+
+        for timer in self.timers.iter() {
+            timer.spawn(args);
+        }
+
+        // collectibles stay collected across checkpoint restarts
+        if args.dyn_assets {
+            for collectible in self.collectibles.iter() {
+                collectible.spawn(args);
+            }
+        }
+
+        for actions in self.actions.iter() {
+            actions.spawn(args);
+        }
+    }
+
+    /// Every signal emitted by these sensors.
+    pub fn emitted(&self) -> impl Iterator<Item = &SerialId> {
+        let volumes = self.volumes.iter().map(|volume| &volume.signal);
+        let timers = self.timers.iter().map(|timer| &timer.signal);
+        let collectibles = self
+            .collectibles
+            .iter()
+            .map(|collectible| &collectible.signal);
+        volumes.chain(timers).chain(collectibles)
+    }
+}
+
+impl SerialVolume {
+    pub fn bind(
+        node: &KdlNode,
+        _load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let signal = node.must_get_id(0, &source);
+
+        let dimensions = node.must_get_scale(1, &source);
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let (signal, dimensions, trans) = (signal, dimensions, trans).merge()?;
+
+        Ok(Self {
+            signal,
+            dimensions,
+            trans,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        args.cmd.spawn((
+            SignalVolume::default(),
+            SignalEmitter::new(&self.signal.value),
+            self.trans,
+            Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z),
+        ));
+    }
+}
+
+impl SerialSignalTimer {
+    pub fn bind(
+        node: &KdlNode,
+        _load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let signal = node.must_get_id(0, &source);
+
+        let period = node.must_get_positive_number("period", &source);
+
+        let on = node.get_number("on", &source);
+
+        let offset = node.get_number("offset", &source);
+
+        let (signal, period, on, offset) = (signal, period, on, offset).merge()?;
+
+        Ok(Self {
+            signal,
+            timer: SignalTimer {
+                period: period as f32,
+                // on for the first half of every period by default
+                on: on.unwrap_or(period / 2.0) as f32,
+                offset: offset.unwrap_or_default() as f32,
+            },
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        args.cmd
+            .spawn((self.timer, SignalEmitter::new(&self.signal.value)));
+    }
+}
+
+impl SerialCollectible {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let signal = node.must_get_id(0, &source);
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| {
+                handle.unwrap_or_else(|| asset_ref::default_plane_material(load_context))
+            });
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let (signal, material, trans) = (signal, material, trans).merge()?;
+
+        Ok(Self {
+            signal,
+            trans,
+            material,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        args.cmd.spawn((
+            SignalCollectible,
+            SignalEmitter::new(&self.signal.value),
+            self.trans,
+            Mesh3d(args.assets.add(Cuboid::from_length(0.3).into())),
+            MeshMaterial3d(self.material.clone()),
+        ));
+    }
+}
+
+impl SerialSignalActions {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let signal = node.must_get_id(0, &source);
+
+        let actions = node.must_children(&source).and_then(|doc| {
+            doc.nodes()
+                .iter()
+                .map(|action| match action.name().value() {
+                    "music" => action
+                        .must_get_handle(0, load_context, &source)
+                        .map(SignalAction::Music),
+                    "kill" => Ok(SignalAction::Kill),
+                    "finish" => Ok(SignalAction::Finish),
+                    name => Err(source.not_a_variant(name, &ACTION_NODES, action.name().span())),
+                })
+                .collect::<Vec<_>>()
+                .merge()
+        });
+
+        let (signal, actions) = (signal, actions).merge()?;
+
+        Ok(Self { signal, actions })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        args.cmd
+            .spawn(SignalActions::new(&self.signal.value, self.actions.clone()));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::serial::level::dynamic::DynamicObjectType;
+use crate::game::levels::serial::level::surface::SerialSurface;
+use crate::game::levels::spawner::Spawner;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+use strum::VariantArray;
+
+/// Spawns `dyn`-like bodies on a schedule, see [`Spawner`].
+///
+/// ```kdl
+/// spawner 0.5 type=sphere interval=3 max=4 lifetime=20 {
+///     pos 0 6 -30
+///     velocity 0 0 2
+/// }
+/// ```
+///
+/// Bodies spawn every `interval` seconds, 2 by default, starting when the level does. The
+/// `velocity` is relative to the spawner.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialSpawner {
+    pub ty: DynamicObjectType,
+    pub dimensions: Vec3,
+    pub interval: f32,
+    pub max_alive: Option<u32>,
+    pub velocity: Vec3,
+    pub lifetime: Option<f32>,
+    pub trans: Transform,
+    pub material: Handle<StandardMaterial>,
+    pub surface: SerialSurface,
+}
+
+impl SerialSpawner {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let ty = node
+            .get_variant("type", DynamicObjectType::VARIANTS, &source)
+            .map(|ty| ty.copied().unwrap_or_default());
+
+        let interval = node
+            .get_positive_number("interval", &source)
+            .map(|interval| interval.unwrap_or(2.0) as f32);
+
+        let max_alive = node
+            .get_positive_number("max", &source)
+            .map(|max| max.map(|max| max.ceil() as u32));
+
+        let lifetime = node
+            .get_positive_number("lifetime", &source)
+            .map(|lifetime| lifetime.map(|lifetime| lifetime as f32));
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| {
+                handle.unwrap_or_else(|| asset_ref::default_plane_material(load_context))
+            });
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let velocity = node.must_children(&source).and_then(|doc| {
+            doc.get("velocity").map_or(Ok(Vec3::ZERO), |velocity| {
+                velocity.must_get_vec3(0, &source)
+            })
+        });
+
+        let dimensions = node.get_scale(0).unwrap_or(Vec3::splat(0.25));
+
+        let surface = SerialSurface::bind(node, load_context, &source);
+
+        let (ty, interval, max_alive, lifetime, material, trans, velocity, surface) = (
+            ty, interval, max_alive, lifetime, material, trans, velocity, surface,
+        )
+            .merge()?;
+
+        let surface = surface.with_material(&material, load_context);
+
+        Ok(Self {
+            ty,
+            dimensions,
+            interval,
+            max_alive,
+            velocity,
+            lifetime,
+            trans,
+            material,
+            surface,
+        })
+    }
+
+    pub fn spawn(&self, args: &mut LevelBuildArgs) {
+        let surface = self.surface.resolve(args);
+        args.cmd.spawn((
+            Spawner {
+                ty: self.ty,
+                dimensions: self.dimensions,
+                mesh: args.assets.add(self.ty.to_mesh(self.dimensions)),
+                material: self.material.clone(),
+                surface,
+                interval: self.interval,
+                max_alive: self.max_alive,
+                velocity: self.velocity,
+                lifetime: self.lifetime,
+                elapsed: self.interval,
+            },
+            self.trans,
+        ));
+    }
+}
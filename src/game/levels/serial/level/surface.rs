@@ -0,0 +1,81 @@
+use crate::game::assets::materials::MATERIAL_EXTENSION;
+use crate::game::levels::serial::error::{BindErrorExt, KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlEntryExt, KdlNodeExt};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::surface::{MATERIAL_SURFACE_LABEL, Surface, SurfacePreset};
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+use strum::VariantArray;
+
+/// The `surface=`, `friction=`, `bounce=` and `rolling_sound=` properties of a node, on top of the
+/// surface its material comes with.
+#[derive(Debug, Default, Clone, Reflect)]
+#[reflect(Debug, Default, Clone)]
+pub struct SerialSurface {
+    pub surface: Surface,
+    pub material: Option<Handle<Surface>>,
+}
+
+impl SerialSurface {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: &Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let preset = node
+            .get_variant("surface", SurfacePreset::VARIANTS, source)
+            .map(|preset| preset.copied());
+
+        let friction = node.get_positive_number("friction", source);
+
+        let bounce = node.entry("bounce").map_or(Ok(None), |entry| {
+            let bounce = entry.as_number(source)?;
+            if (0.0..=1.0).contains(&bounce) {
+                Ok(Some(bounce))
+            } else {
+                Err(source.out_of_range(bounce, 0, 1, entry.span()))
+            }
+        });
+
+        let rolling_sound = node.get_handle("rolling_sound", load_context, source);
+
+        let (preset, friction, bounce, rolling_sound) =
+            (preset, friction, bounce, rolling_sound).merge()?;
+
+        Ok(Self {
+            surface: Surface {
+                preset,
+                friction: friction.map(|friction| friction as f32),
+                bounce: bounce.map(|bounce| bounce as f32),
+                rolling_sound,
+            },
+            material: None,
+        })
+    }
+
+    /// Falls back to the surface of `material` if it's a `.material.json`.
+    pub fn with_material(
+        self,
+        material: &Handle<StandardMaterial>,
+        load_context: &mut LoadContext,
+    ) -> Self {
+        let material = material
+            .path()
+            .filter(|path| {
+                path.label().is_none()
+                    && path.get_full_extension().as_deref() == Some(MATERIAL_EXTENSION)
+            })
+            .map(|path| load_context.load(path.clone().with_label(MATERIAL_SURFACE_LABEL)));
+        Self { material, ..self }
+    }
+
+    pub fn resolve(&self, args: &LevelBuildArgs) -> Surface {
+        let material = self
+            .material
+            .as_ref()
+            .and_then(|material| args.surfaces.get(material));
+        self.surface.or(material)
+    }
+}
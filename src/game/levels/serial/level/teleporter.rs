@@ -0,0 +1,100 @@
+use crate::game::assets::asset_ref;
+use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt, SerialId};
+use crate::game::levels::serial::level::LevelBuildArgs;
+use crate::game::levels::teleporter::Teleporter;
+use avian3d::prelude::*;
+use bevy::asset::LoadContext;
+use bevy::prelude::*;
+use kdl::KdlNode;
+use std::sync::Arc;
+
+/// A teleporter that sends things to the teleporter named by `to=`. Teleporters without one only
+/// act as exits.
+///
+/// ```kdl
+/// teleporter a 1 2 0.2 to=b { pos 0 1 -5; }
+/// teleporter b 1 2 0.2 to=a {
+///     pos 20 1 -5
+///     rot y 90
+/// }
+/// ```
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Debug, Clone)]
+pub struct SerialTeleporter {
+    pub id: SerialId,
+    pub to: Option<SerialId>,
+    pub dimensions: Vec3,
+    pub material: Handle<StandardMaterial>,
+    pub trans: Transform,
+}
+
+impl SerialTeleporter {
+    pub fn bind(
+        node: &KdlNode,
+        load_context: &mut LoadContext,
+        source: Arc<String>,
+    ) -> Result<Self, KdlBindError> {
+        let id = node.must_get_id(0, &source);
+
+        let to = node.get_id("to", &source);
+
+        let dimensions = node.must_get_scale(1, &source);
+
+        let material = node
+            .get_handle("material", load_context, &source)
+            .map(|handle| handle.unwrap_or_else(|| asset_ref::default_text_material(load_context)));
+
+        let trans = node
+            .must_children(&source)
+            .and_then(|doc| doc.get_transform(&source));
+
+        let (id, to, dimensions, material, trans) =
+            (id, to, dimensions, material, trans).merge()?;
+
+        Ok(Self {
+            id,
+            to,
+            dimensions,
+            material,
+            trans,
+        })
+    }
+
+    /// Spawns the teleporter, `teleporters` are all of the level's teleporters to find the exit in.
+    pub fn spawn(&self, teleporters: &[SerialTeleporter], args: &mut LevelBuildArgs) {
+        let exit = self.to.as_ref().and_then(|to| {
+            teleporters
+                .iter()
+                .find(|teleporter| teleporter.id.value == to.value)
+                .map(|teleporter| teleporter.trans)
+        });
+
+        args.cmd.spawn((
+            Teleporter { exit },
+            self.trans,
+            Mesh3d(args.assets.add(Cuboid::from_size(self.dimensions).into())),
+            MeshMaterial3d(self.material.clone()),
+            Collider::cuboid(self.dimensions.x, self.dimensions.y, self.dimensions.z),
+        ));
+    }
+}
+
+pub fn check_teleporter_ids(
+    teleporters: &[SerialTeleporter],
+    source: &Arc<String>,
+) -> Result<(), KdlBindError> {
+    let ids = SerialId::unique(
+        "teleporter",
+        teleporters.iter().map(|teleporter| &teleporter.id),
+        source,
+    )?;
+
+    teleporters
+        .iter()
+        .filter_map(|teleporter| teleporter.to.as_ref())
+        .map(|to| to.must_refer_to("teleporter", &ids, source))
+        .collect::<Vec<_>>()
+        .merge()
+        .map(|_| ())
+}
@@ -1,8 +1,9 @@
 use crate::game::assets::asset_ref;
 use crate::game::levels::LevelObject;
 use crate::game::levels::serial::error::{KdlBindError, MergeKdlBindError};
-use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt};
+use crate::game::levels::serial::kdl_utils::{KdlDocumentExt, KdlNodeExt, SerialId};
 use crate::game::levels::serial::level::{DEFAULT_TEXT_PT, LevelBuildArgs};
+use crate::game::levels::signal::SignalVisibility;
 use bevy::prelude::*;
 use bevy_rich_text3d::{Text3d, Text3dStyling, TextAlign};
 use kdl::KdlNode;
@@ -20,6 +21,8 @@ pub struct SerialText {
     pub pt: f32,
     pub font: Handle<Font>,
     pub align: SerialAlign,
+    /// Signal that shows the text, the text is always shown without one.
+    pub when: Option<SerialId>,
 }
 
 #[derive(Debug, Default, Copy, Clone, Reflect, strum::VariantArray, strum::Display)]
@@ -58,13 +61,15 @@ impl SerialText {
             .get_handle("material", load_context, &source)
             .map(|asset| asset.unwrap_or_else(|| asset_ref::default_text_material(load_context)));
 
+        let when = node.get_id("when", &source);
+
         let trans = node
             .children()
             .map_or(Ok(None), |doc| doc.get_transform(&source).map(Some))
             .map(|trans| trans.unwrap_or_default());
 
-        let (text, pt, font, align, material, trans) =
-            (text, pt, font, align, material, trans).merge()?;
+        let (text, pt, font, align, material, when, trans) =
+            (text, pt, font, align, material, when, trans).merge()?;
 
         Ok(Self {
             text,
@@ -73,6 +78,7 @@ impl SerialText {
             pt: pt as f32,
             font,
             align,
+            when,
         })
     }
 
@@ -85,7 +91,7 @@ impl SerialText {
                 &args.fonts[&args.preloads.text_font().id()]
             })
             .clone();
-        args.cmd.spawn((
+        let mut commands = args.cmd.spawn((
             LevelObject,
             self.trans,
             Text3d::new(self.text.clone()),
@@ -99,6 +105,11 @@ impl SerialText {
             Mesh3d::default(),
             MeshMaterial3d(self.material.clone()),
         ));
+        if let Some(when) = &self.when {
+            commands.insert(SignalVisibility {
+                signal: when.value.clone(),
+            });
+        }
     }
 }
 
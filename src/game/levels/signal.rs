@@ -0,0 +1,336 @@
+//! Named signals that connect level objects.
+//!
+//! Sensors (volumes, buttons, timers and collectibles) drive a [`SignalEmitter`], and a signal is
+//! on while any of its emitters are. Actuators (doors, [`SignalVisibility`] and [`SignalActions`])
+//! react to signals turning on and off.
+//!
+//! Music triggers, death volumes and the finish point are volumes with a local emitter that only
+//! drives the actions on their own entity.
+
+use crate::game::game_state::GameState;
+use crate::game::levels::button::ButtonPresser;
+use crate::game::levels::death::{Kill, Killable};
+use crate::game::levels::finish_point::finish_level;
+use crate::game::levels::gem::GemCount;
+use crate::game::levels::pickup::{Pickup, PickupTouched};
+use crate::game::levels::{DynamicLevelObject, LevelObject, colliding_bodies};
+use crate::game::logic::Player;
+use crate::game::music::{BackgroundMusic, switch_music};
+use crate::game::state::AppState;
+use crate::game::timer::LevelTimer;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct SignalPlugin;
+
+impl Plugin for SignalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Signals>()
+            .add_observer(collect_signal)
+            .configure_sets(
+                Update,
+                (SignalSystems::Sense, SignalSystems::React)
+                    .chain()
+                    .run_if(in_state(AppState::Game)),
+            )
+            .add_systems(
+                Update,
+                (
+                    (detect_volumes, tick_signal_timers).in_set(SignalSystems::Sense),
+                    collect_signals
+                        .after(SignalSystems::Sense)
+                        .before(SignalSystems::React),
+                    (run_signal_actions, show_signalled).in_set(SignalSystems::React),
+                    remember_emitters.after(SignalSystems::React),
+                ),
+            )
+            .add_systems(OnExit(AppState::Game), clear_signals);
+    }
+}
+
+/// Sensors update their emitters in [`SignalSystems::Sense`], and actuators read [`Signals`] in
+/// [`SignalSystems::React`].
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, SystemSet)]
+pub enum SignalSystems {
+    Sense,
+    React,
+}
+
+/// The signals that are currently on.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Resource, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Resource)]
+pub struct Signals {
+    on: HashSet<String>,
+    previous: HashSet<String>,
+}
+
+impl Signals {
+    pub fn is_on(&self, signal: &str) -> bool {
+        self.on.contains(signal)
+    }
+
+    /// Whether the signal turned on this frame.
+    pub fn turned_on(&self, signal: &str) -> bool {
+        self.on.contains(signal) && !self.previous.contains(signal)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+pub struct SignalEmitter {
+    /// The named signal this drives, `None` if it only drives the [`SignalActions`] on its own
+    /// entity.
+    pub signal: Option<String>,
+    pub on: bool,
+    /// Whether `on` was set in the previous frame.
+    pub was_on: bool,
+}
+
+impl SignalEmitter {
+    pub fn new(signal: impl Into<String>) -> Self {
+        Self {
+            signal: Some(signal.into()),
+            ..default()
+        }
+    }
+
+    /// An emitter that isn't part of any named signal.
+    pub fn local() -> Self {
+        Self::default()
+    }
+
+    /// Whether the emitter turned on this frame.
+    pub fn turned_on(&self) -> bool {
+        self.on && !self.was_on
+    }
+}
+
+/// Which bodies a [`SignalVolume`] reacts to.
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+pub enum Sensed {
+    #[default]
+    Player,
+    Killable,
+    /// Anything that can press buttons.
+    Presser,
+}
+
+/// Emits its signal while a body it senses is inside.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+#[require(
+    LevelObject,
+    Transform,
+    Sensor,
+    CollidingEntities,
+    SignalEmitter::local()
+)]
+pub struct SignalVolume {
+    pub senses: Sensed,
+    /// The sensed bodies inside, a body with several colliders is only listed once.
+    pub inside: Vec<Entity>,
+    /// The sensed bodies that came in this frame.
+    pub entered: Vec<Entity>,
+}
+
+impl SignalVolume {
+    pub fn sensing(senses: Sensed) -> Self {
+        Self {
+            senses,
+            ..default()
+        }
+    }
+}
+
+/// Emits its signal for `on` seconds out of every `period`, timed from the start of the run.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+#[require(LevelObject)]
+pub struct SignalTimer {
+    pub period: f32,
+    pub on: f32,
+    pub offset: f32,
+}
+
+/// Emits its signal from the moment the player picks it up.
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+#[require(DynamicLevelObject, Pickup)]
+pub struct SignalCollectible;
+
+/// Shows the entity only while its signal is on.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+#[require(Visibility)]
+pub struct SignalVisibility {
+    pub signal: String,
+}
+
+/// Runs actions every time its signal turns on.
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+#[require(LevelObject)]
+pub struct SignalActions {
+    /// The named signal to react to, `None` to react to the [`SignalEmitter`] on the same entity.
+    pub signal: Option<String>,
+    pub actions: Vec<SignalAction>,
+}
+
+impl SignalActions {
+    pub fn new(signal: impl Into<String>, actions: Vec<SignalAction>) -> Self {
+        Self {
+            signal: Some(signal.into()),
+            actions,
+        }
+    }
+
+    /// Actions run by the sensor on the same entity.
+    pub fn local(actions: Vec<SignalAction>) -> Self {
+        Self {
+            signal: None,
+            actions,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum SignalAction {
+    /// Switches the background music.
+    Music(Handle<AudioSource>),
+    /// Kills every body that comes into the signal's volumes, or the player if no volume set it
+    /// off.
+    Kill,
+    /// Finishes the level if all of its gems are collected.
+    Finish,
+}
+
+pub fn detect_volumes(
+    volumes: Query<(&CollidingEntities, &mut SignalVolume, &mut SignalEmitter)>,
+    colliders: Query<&ColliderOf>,
+    players: Query<(), With<Player>>,
+    killables: Query<(), With<Killable>>,
+    pressers: Query<(), With<ButtonPresser>>,
+) {
+    for (colliding, mut volume, mut emitter) in volumes {
+        let inside = colliding_bodies(colliding, &colliders)
+            .into_iter()
+            .filter(|entity| match volume.senses {
+                Sensed::Player => players.contains(*entity),
+                Sensed::Killable => killables.contains(*entity),
+                Sensed::Presser => pressers.contains(*entity),
+            })
+            .collect::<Vec<_>>();
+
+        volume.entered = inside
+            .iter()
+            .copied()
+            .filter(|entity| !volume.inside.contains(entity))
+            .collect();
+        emitter.on = !inside.is_empty();
+        volume.inside = inside;
+    }
+}
+
+fn tick_signal_timers(
+    timers: Query<(&SignalTimer, &mut SignalEmitter)>,
+    level_timer: Res<LevelTimer>,
+) {
+    let elapsed = level_timer.elapsed.as_secs_f32();
+    for (timer, mut emitter) in timers {
+        emitter.on = (elapsed + timer.offset).rem_euclid(timer.period) < timer.on;
+    }
+}
+
+fn collect_signal(
+    touched: On<PickupTouched>,
+    mut collectibles: Query<(&mut SignalEmitter, &mut Pickup), With<SignalCollectible>>,
+) {
+    if let Ok((mut emitter, mut pickup)) = collectibles.get_mut(touched.entity) {
+        emitter.on = true;
+        pickup.taken = true;
+    }
+}
+
+fn collect_signals(mut signals: ResMut<Signals>, emitters: Query<&SignalEmitter>) {
+    let on = emitters
+        .iter()
+        .filter(|emitter| emitter.on)
+        .filter_map(|emitter| emitter.signal.clone())
+        .collect();
+    signals.previous = std::mem::replace(&mut signals.on, on);
+}
+
+fn run_signal_actions(
+    mut cmd: Commands,
+    signal_actions: Query<(Entity, &SignalActions)>,
+    emitters: Query<&SignalEmitter>,
+    volumes: Query<(&SignalVolume, &SignalEmitter)>,
+    players: Query<Entity, With<Player>>,
+    background_music: Query<(Entity, &BackgroundMusic)>,
+    mut kill_msg: MessageWriter<Kill>,
+    mut state: ResMut<NextState<GameState>>,
+    mut timer: ResMut<LevelTimer>,
+    gems: Res<GemCount>,
+    signals: Res<Signals>,
+) {
+    for (entity, signal_actions) in signal_actions {
+        let (turned_on, entered) = match &signal_actions.signal {
+            Some(signal) => (
+                signals.turned_on(signal),
+                volumes
+                    .iter()
+                    .filter(|(_, emitter)| emitter.signal.as_ref() == Some(signal))
+                    .flat_map(|(volume, _)| volume.entered.iter().copied())
+                    .collect::<Vec<_>>(),
+            ),
+            None => (
+                emitters.get(entity).is_ok_and(SignalEmitter::turned_on),
+                volumes
+                    .get(entity)
+                    .map(|(volume, _)| volume.entered.clone())
+                    .unwrap_or_default(),
+            ),
+        };
+
+        for action in signal_actions.actions.iter() {
+            match action {
+                SignalAction::Music(audio) if turned_on => {
+                    switch_music(&mut cmd, audio, &background_music);
+                }
+                SignalAction::Kill if !entered.is_empty() => {
+                    kill_msg.write_batch(entered.iter().copied().map(Kill::new));
+                }
+                SignalAction::Kill if turned_on => {
+                    kill_msg.write_batch(players.iter().map(Kill::new));
+                }
+                SignalAction::Finish if turned_on => finish_level(&gems, &mut state, &mut timer),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn show_signalled(query: Query<(&SignalVisibility, &mut Visibility)>, signals: Res<Signals>) {
+    for (signal_visibility, mut visibility) in query {
+        let target = if signals.is_on(&signal_visibility.signal) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(target);
+    }
+}
+
+fn remember_emitters(emitters: Query<&mut SignalEmitter>) {
+    for mut emitter in emitters {
+        emitter.was_on = emitter.on;
+    }
+}
+
+fn clear_signals(mut signals: ResMut<Signals>) {
+    *signals = Signals::default();
+}
@@ -0,0 +1,141 @@
+//! Spawners that keep dropping dynamic bodies into the level.
+
+use crate::game::game_state::GameState;
+use crate::game::levels::LevelObject;
+use crate::game::levels::button::ButtonPresser;
+use crate::game::levels::death::{Killable, Killed};
+use crate::game::levels::gravity::GravityUp;
+use crate::game::levels::serial::level::dynamic::DynamicObjectType;
+use crate::game::levels::surface::Surface;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct SpawnerPlugin;
+
+impl Plugin for SpawnerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(despawn_killed_bodies).add_systems(
+            Update,
+            (run_spawners, expire_spawned).run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Spawns a body every `interval` seconds, as long as fewer than `max_alive` of its bodies are
+/// left.
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[require(LevelObject, Transform)]
+pub struct Spawner {
+    pub ty: DynamicObjectType,
+    pub dimensions: Vec3,
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+    pub surface: Surface,
+    pub interval: f32,
+    pub max_alive: Option<u32>,
+    /// Initial velocity of the bodies, relative to the spawner.
+    pub velocity: Vec3,
+    /// Seconds before a body despawns on its own.
+    pub lifetime: Option<f32>,
+    /// Seconds since the last body was spawned.
+    pub elapsed: f32,
+}
+
+impl Spawner {
+    /// Whether to spawn a body now. Spawners that are full wait until a body is gone, then spawn
+    /// straight away.
+    pub fn tick(&mut self, delta: f32, alive: u32) -> bool {
+        self.elapsed += delta;
+        if self.elapsed >= self.interval && self.max_alive.is_none_or(|max| alive < max) {
+            self.elapsed = 0.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A body spawned by a [`Spawner`]. Touching a death collider despawns it.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+#[require(LevelObject, Killable, ButtonPresser, GravityUp)]
+pub struct Spawned {
+    pub spawner: Entity,
+    /// Seconds left before the body despawns.
+    pub remaining: Option<f32>,
+}
+
+fn run_spawners(
+    mut cmd: Commands,
+    spawners: Query<(Entity, &mut Spawner, &GlobalTransform)>,
+    spawned: Query<&Spawned>,
+    time: Res<Time>,
+) {
+    for (entity, mut spawner, trans) in spawners {
+        let alive = spawned.iter().filter(|body| body.spawner == entity).count() as u32;
+        if !spawner.tick(time.delta_secs(), alive) {
+            continue;
+        }
+
+        let trans = trans.compute_transform();
+        cmd.spawn((
+            Spawned {
+                spawner: entity,
+                remaining: spawner.lifetime,
+            },
+            trans,
+            Mesh3d(spawner.mesh.clone()),
+            MeshMaterial3d(spawner.material.clone()),
+            RigidBody::Dynamic,
+            spawner.ty.to_collider(spawner.dimensions),
+            LinearVelocity(trans.rotation * spawner.velocity),
+            spawner.surface.physics(),
+        ));
+    }
+}
+
+fn expire_spawned(mut cmd: Commands, bodies: Query<(Entity, &mut Spawned)>, time: Res<Time>) {
+    for (entity, mut body) in bodies {
+        if let Some(remaining) = &mut body.remaining {
+            *remaining -= time.delta_secs();
+            if *remaining <= 0.0 {
+                cmd.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+fn despawn_killed_bodies(killed: On<Killed>, mut cmd: Commands, bodies: Query<(), With<Spawned>>) {
+    if bodies.contains(killed.entity) {
+        cmd.entity(killed.entity).try_despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawner_tick() {
+        let mut spawner = Spawner {
+            ty: DynamicObjectType::Sphere,
+            dimensions: Vec3::splat(0.5),
+            mesh: Handle::default(),
+            material: Handle::default(),
+            surface: Surface::default(),
+            interval: 2.0,
+            max_alive: Some(1),
+            velocity: Vec3::ZERO,
+            lifetime: None,
+            elapsed: 2.0,
+        };
+
+        assert!(spawner.tick(0.0, 0));
+        assert!(!spawner.tick(1.0, 0));
+        assert!(!spawner.tick(1.0, 1));
+        assert!(!spawner.tick(1.0, 1));
+        assert!(spawner.tick(0.0, 0));
+    }
+}
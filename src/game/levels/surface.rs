@@ -0,0 +1,272 @@
+//! How surfaces feel to roll on: friction, bounciness and special surfaces like ice or mud.
+//!
+//! A [`Surface`] can be set on level objects directly, or carried by a `.material.json` so every
+//! object using that material feels the same.
+
+use crate::game::game_state::GameState;
+use crate::game::logic::Player;
+use avian3d::prelude::*;
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Label of the [`Surface`] sub-asset of a `.material.json`.
+pub const MATERIAL_SURFACE_LABEL: &str = "surface";
+
+/// Angular speed at which rolling sounds play at full volume.
+const ROLLING_SOUND_FULL_SPEED: f32 = 20.0;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct SurfacePlugin;
+
+impl Plugin for SurfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            drag_through_surfaces.run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            play_rolling_sounds.run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Hash,
+    Reflect,
+    Serialize,
+    Deserialize,
+    strum::VariantArray,
+    strum::Display,
+)]
+#[reflect(Debug, Clone, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SurfacePreset {
+    Ice,
+    Rubber,
+    Sticky,
+    /// Grippy, and slows down the player while touching it.
+    Mud,
+}
+
+impl SurfacePreset {
+    pub fn friction(self) -> f32 {
+        match self {
+            SurfacePreset::Ice => 0.02,
+            SurfacePreset::Rubber => 0.9,
+            SurfacePreset::Sticky => 2.0,
+            SurfacePreset::Mud => 1.0,
+        }
+    }
+
+    pub fn bounce(self) -> f32 {
+        match self {
+            SurfacePreset::Ice => 0.05,
+            SurfacePreset::Rubber => 0.85,
+            SurfacePreset::Sticky | SurfacePreset::Mud => 0.0,
+        }
+    }
+
+    /// How quickly the surface slows down the player, per second.
+    pub fn drag(self) -> f32 {
+        match self {
+            SurfacePreset::Mud => 3.0,
+            SurfacePreset::Ice | SurfacePreset::Rubber | SurfacePreset::Sticky => 0.0,
+        }
+    }
+}
+
+/// Physical properties of a surface. Anything left unset falls back to the preset, then to the
+/// physics engine defaults.
+#[derive(Debug, Default, Clone, PartialEq, Asset, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub struct Surface {
+    pub preset: Option<SurfacePreset>,
+    pub friction: Option<f32>,
+    pub bounce: Option<f32>,
+    /// Looped while the player rolls on the surface.
+    #[dependency]
+    pub rolling_sound: Option<Handle<AudioSource>>,
+}
+
+impl Surface {
+    /// Fills in anything this surface leaves unset from `fallback`.
+    pub fn or(&self, fallback: Option<&Surface>) -> Surface {
+        let Some(fallback) = fallback else {
+            return self.clone();
+        };
+        Surface {
+            preset: self.preset.or(fallback.preset),
+            friction: self.friction.or(fallback.friction),
+            bounce: self.bounce.or(fallback.bounce),
+            rolling_sound: self
+                .rolling_sound
+                .clone()
+                .or_else(|| fallback.rolling_sound.clone()),
+        }
+    }
+
+    /// Components that give a collider this surface.
+    pub fn physics(&self) -> (Friction, Restitution, SurfaceEffects) {
+        let friction = self
+            .friction
+            .or(self.preset.map(SurfacePreset::friction))
+            .map_or_else(Friction::default, |friction| {
+                let combine = combine_rule(friction, Friction::default().dynamic_coefficient);
+                Friction::new(friction).with_combine_rule(combine)
+            });
+
+        let restitution = self
+            .bounce
+            .or(self.preset.map(SurfacePreset::bounce))
+            .map_or_else(Restitution::default, |bounce| {
+                let combine = combine_rule(bounce, Restitution::default().coefficient);
+                Restitution::new(bounce).with_combine_rule(combine)
+            });
+
+        let effects = SurfaceEffects {
+            drag: self.preset.map_or(0.0, SurfacePreset::drag),
+            rolling_sound: self.rolling_sound.clone(),
+        };
+
+        (friction, restitution, effects)
+    }
+}
+
+/// Picks the combine rule that makes `value` win against the default coefficient of whatever
+/// touches the surface.
+pub fn combine_rule(value: f32, default: f32) -> CoefficientCombine {
+    if value < default {
+        CoefficientCombine::Min
+    } else {
+        CoefficientCombine::Max
+    }
+}
+
+/// What a surface does to the player beyond friction and bounce.
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+pub struct SurfaceEffects {
+    pub drag: f32,
+    pub rolling_sound: Option<Handle<AudioSource>>,
+}
+
+/// Rolling sound attached to the player.
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Component)]
+pub struct RollingSound(pub Handle<AudioSource>);
+
+/// Colliders that are touching `entity`.
+fn touching<'a>(collisions: &'a Collisions, entity: Entity) -> impl Iterator<Item = Entity> + 'a {
+    collisions
+        .collisions_with(entity)
+        .filter(|pair| pair.is_touching())
+        .map(move |pair| {
+            if pair.collider1 == entity {
+                pair.collider2
+            } else {
+                pair.collider1
+            }
+        })
+}
+
+fn drag_through_surfaces(
+    players: Query<(Entity, &mut LinearVelocity), With<Player>>,
+    surfaces: Query<&SurfaceEffects>,
+    collisions: Collisions,
+    time: Res<Time>,
+) {
+    for (player, mut velocity) in players {
+        let drag = touching(&collisions, player)
+            .filter_map(|entity| surfaces.get(entity).ok())
+            .map(|effects| effects.drag)
+            .fold(0.0, f32::max);
+
+        if drag > 0.0 {
+            velocity.0 /= 1.0 + drag * time.delta_secs();
+        }
+    }
+}
+
+fn play_rolling_sounds(
+    mut cmd: Commands,
+    players: Query<(Entity, &AngularVelocity, Option<&Children>), With<Player>>,
+    surfaces: Query<&SurfaceEffects>,
+    mut rolling: Query<(&RollingSound, Option<&mut AudioSink>)>,
+    collisions: Collisions,
+) {
+    for (player, angular, children) in players {
+        let sound = touching(&collisions, player)
+            .filter_map(|entity| surfaces.get(entity).ok())
+            .find_map(|effects| effects.rolling_sound.clone());
+
+        let mut playing = false;
+        for child in children.into_iter().flatten() {
+            let Ok((rolling_sound, sink)) = rolling.get_mut(*child) else {
+                continue;
+            };
+
+            if sound.as_ref() == Some(&rolling_sound.0) {
+                playing = true;
+                if let Some(mut sink) = sink {
+                    let loudness = (angular.length() / ROLLING_SOUND_FULL_SPEED).min(1.0);
+                    sink.set_volume(Volume::Linear(loudness));
+                }
+            } else {
+                cmd.entity(*child).despawn();
+            }
+        }
+
+        if !playing && let Some(sound) = sound {
+            cmd.spawn((
+                RollingSound(sound.clone()),
+                AudioPlayer(sound),
+                PlaybackSettings::LOOP.with_volume(Volume::SILENT),
+                ChildOf(player),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surface_fallback() {
+        let node = Surface {
+            bounce: Some(0.5),
+            ..default()
+        };
+        let material = Surface {
+            preset: Some(SurfacePreset::Ice),
+            bounce: Some(0.1),
+            ..default()
+        };
+
+        let surface = node.or(Some(&material));
+        assert_eq!(surface.preset, Some(SurfacePreset::Ice));
+        assert_eq!(surface.bounce, Some(0.5));
+
+        let (friction, restitution, effects) = surface.physics();
+        assert_eq!(friction.dynamic_coefficient, SurfacePreset::Ice.friction());
+        assert_eq!(friction.combine_rule, CoefficientCombine::Min);
+        assert_eq!(restitution.coefficient, 0.5);
+        assert_eq!(restitution.combine_rule, CoefficientCombine::Max);
+        assert_eq!(effects.drag, 0.0);
+    }
+
+    #[test]
+    fn test_surface_defaults() {
+        let (friction, restitution, _) = Surface::default().physics();
+        assert_eq!(friction, Friction::default());
+        assert_eq!(restitution, Restitution::default());
+    }
+}
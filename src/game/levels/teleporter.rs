@@ -0,0 +1,94 @@
+//! Teleporters that move dynamic bodies to another teleporter, keeping their momentum.
+
+use crate::game::camera::{CameraUp, PlayerCamera};
+use crate::game::game_state::GameState;
+use crate::game::levels::{LevelObject, colliding_bodies};
+use crate::game::logic::Player;
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct TeleporterPlugin;
+
+impl Plugin for TeleporterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, teleport.run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Sends dynamic bodies that enter it to `exit`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+#[require(LevelObject, Transform, Sensor, CollidingEntities)]
+pub struct Teleporter {
+    pub exit: Option<Transform>,
+}
+
+/// Marks a body that came out of a teleporter, so it isn't sent back until it has left every
+/// teleporter.
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+#[component(storage = "SparseSet")]
+pub struct Teleported;
+
+fn teleport(
+    mut cmd: Commands,
+    teleporters: Query<(&Teleporter, &GlobalTransform, &CollidingEntities)>,
+    colliders: Query<&ColliderOf>,
+    mut bodies: Query<
+        (
+            &RigidBody,
+            &mut Position,
+            &mut Rotation,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+            Has<Player>,
+        ),
+        Without<Teleported>,
+    >,
+    teleported: Query<Entity, With<Teleported>>,
+    mut cameras: Query<(&mut PlayerCamera, &CameraUp)>,
+) {
+    let inside = teleporters
+        .iter()
+        .flat_map(|(_, _, colliding)| colliding_bodies(colliding, &colliders))
+        .collect::<HashSet<_>>();
+    for entity in teleported {
+        if !inside.contains(&entity) {
+            cmd.entity(entity).remove::<Teleported>();
+        }
+    }
+
+    for (teleporter, transform, colliding) in teleporters {
+        let Some(exit) = teleporter.exit else {
+            continue;
+        };
+        // turns the entry frame into the exit frame
+        let turn = exit.rotation * transform.rotation().inverse();
+
+        for entity in colliding_bodies(colliding, &colliders) {
+            let Ok((body, mut position, mut rotation, mut linear, mut angular, is_player)) =
+                bodies.get_mut(entity)
+            else {
+                continue;
+            };
+            if !body.is_dynamic() {
+                continue;
+            }
+
+            position.0 = exit.translation;
+            rotation.0 = turn * rotation.0;
+            linear.0 = turn * linear.0;
+            angular.0 = turn * angular.0;
+            cmd.entity(entity).insert(Teleported);
+
+            if is_player {
+                for (mut camera, camera_up) in cameras.iter_mut() {
+                    // only the part of the turn around the camera's up changes its yaw
+                    camera.yaw += 2.0 * f32::atan2(turn.xyz().dot(*camera_up.0), turn.w);
+                }
+            }
+        }
+    }
+}
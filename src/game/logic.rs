@@ -1,9 +1,13 @@
 use crate::game::assets::preload::Preloads;
-use crate::game::camera::PlayerCamera;
+use crate::game::camera::{CameraUp, PlayerCamera};
 use crate::game::game_state::GameState;
 use crate::game::input::PlayerInput;
 use crate::game::levels::button::ButtonPresser;
-use crate::game::levels::death::{Kill, Killable, PlayerDiedEvent};
+use crate::game::levels::checkpoint::ActiveCheckpoint;
+use crate::game::levels::death::{Killable, Killed, PlayerDiedEvent};
+use crate::game::levels::gravity::{GravityUp, up_frame};
+use crate::game::levels::key::Inventory;
+use crate::game::levels::powerup::PowerUpSlot;
 use crate::game::levels::{LevelReadyEvent, LevelRestartEvent, PlayerSpawnPoint};
 use crate::game::state::AppState;
 use avian3d::prelude::*;
@@ -12,20 +16,29 @@ use std::f32::consts::PI;
 
 pub const MOVEMENT_ACCELERATION: f32 = 30.0 * PI;
 pub const JUMP_VELOCITY: f32 = 4.0;
+pub const PLAYER_LINEAR_DAMPING: f32 = 0.25;
+/// How quickly the camera turns to follow a change of gravity.
+pub const CAMERA_UP_SMOOTHING: f32 = 4.0;
 
 #[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(add_player)
+        app.init_resource::<TickInput>()
+            .add_observer(add_player)
             .add_observer(reset_player)
             .add_observer(on_collision_start)
             .add_observer(on_collision_stop)
+            .add_observer(kill_player)
             .add_systems(OnExit(AppState::Game), remove_player)
             .add_systems(
-                Update,
-                (move_player, jump_player, kill_player).run_if(in_state(GameState::Playing)),
+                FixedPreUpdate,
+                read_tick_input.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (move_player, jump_player).run_if(in_state(GameState::Playing)),
             )
             .add_systems(Update, move_camera.run_if(in_state(AppState::Game)));
     }
@@ -33,6 +46,7 @@ impl Plugin for GamePlugin {
 
 #[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
 #[reflect(Debug, Default, Clone, PartialEq, Hash, Component)]
+#[require(GravityUp, PowerUpSlot, Inventory)]
 pub struct Player;
 
 #[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Reflect)]
@@ -40,9 +54,46 @@ pub struct Player;
 #[component(storage = "SparseSet")]
 pub struct Grounded;
 
+/// The gameplay inputs of the current physics tick, read from [`PlayerInput`], along with the
+/// camera the movement is relative to.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Resource, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Resource)]
+pub struct TickInput {
+    pub movement: Vec2,
+    pub jump: bool,
+    pub activate: bool,
+    /// The yaw of the [`PlayerCamera`].
+    pub yaw: f32,
+    pub camera_up: CameraUp,
+}
+
+impl TickInput {
+    /// Turns the movement input into the axis the ball spins around, relative to the camera.
+    pub fn roll_axis(&self) -> Vec3 {
+        let camera_up = self.camera_up.0;
+        let up = *camera_up;
+        let camera = PlayerCamera {
+            yaw: self.yaw,
+            ..default()
+        };
+
+        // build a frame matrix
+        let y = up_frame(camera_up) * camera.get_looking();
+        let x = y.cross(up);
+
+        // multiply that matrix by the force vector
+        up.cross(self.movement.x * x + self.movement.y * y)
+    }
+}
+
 pub fn spawn_transform(
     spawn_point: Query<&Transform, (With<PlayerSpawnPoint>, Without<Player>)>,
+    checkpoint: &ActiveCheckpoint,
 ) -> Transform {
+    if let Some(respawn) = checkpoint.0 {
+        return respawn;
+    }
+
     let spawn_point = spawn_point.iter().copied().collect::<Vec<_>>();
     match spawn_point.iter().next() {
         None => Transform::from_translation(Vec3::new(0.0, 0.5, 0.0)),
@@ -54,9 +105,10 @@ fn add_player(
     _event: On<LevelReadyEvent>,
     mut cmd: Commands,
     spawn_point: Query<&Transform, (With<PlayerSpawnPoint>, Without<Player>)>,
+    checkpoint: Res<ActiveCheckpoint>,
     preloads: Res<Preloads>,
 ) {
-    let spawn_transform = spawn_transform(spawn_point);
+    let spawn_transform = spawn_transform(spawn_point, &checkpoint);
 
     let collider = Collider::sphere(0.25);
 
@@ -66,7 +118,7 @@ fn add_player(
         RigidBody::Dynamic,
         collider,
         AngularDamping(0.25),
-        LinearDamping(0.25),
+        LinearDamping(PLAYER_LINEAR_DAMPING),
         CollisionEventsEnabled,
         Killable,
         InheritedVisibility::default(),
@@ -82,8 +134,9 @@ fn reset_player(
         (&mut Transform, &mut AngularVelocity, &mut LinearVelocity),
         (With<Player>, Without<PlayerSpawnPoint>),
     >,
+    checkpoint: Res<ActiveCheckpoint>,
 ) {
-    let spawn_transform = spawn_transform(spawn_point);
+    let spawn_transform = spawn_transform(spawn_point, &checkpoint);
 
     for (mut transform, mut ang_vel, mut lin_vel) in player {
         *transform = spawn_transform;
@@ -98,57 +151,70 @@ fn remove_player(mut cmd: Commands, players: Query<Entity, With<Player>>) {
     }
 }
 
-fn move_player(
-    forces: Query<&mut AngularVelocity, With<Player>>,
-    camera: Single<&PlayerCamera>,
+// the inputs keep coming in every frame, but are only used once per tick, the latest movement is
+// kept for ticks that run without a new frame in between
+pub fn read_tick_input(
+    mut tick: ResMut<TickInput>,
+    mut movement: Local<Vec2>,
     mut inputs: MessageReader<PlayerInput>,
-    time: Res<Time>,
+    camera: Single<(&PlayerCamera, &CameraUp)>,
 ) {
-    // build a frame matrix
-    let y = camera.get_looking();
-    let x = y.cross(Vec3::Y);
+    let (camera, camera_up) = *camera;
 
-    let mut movement = Vec3::default();
+    *tick = TickInput {
+        yaw: camera.yaw,
+        camera_up: *camera_up,
+        ..default()
+    };
     for input in inputs.read() {
-        if let PlayerInput::Movement(force) = input {
-            // multiply that matrix by the force vector
-            movement += force.x * x + force.y * y;
+        match input {
+            PlayerInput::Movement(latest) => *movement = *latest,
+            PlayerInput::Jump => tick.jump = true,
+            PlayerInput::Activate => tick.activate = true,
+            _ => {}
         }
     }
+    tick.movement = *movement;
+}
 
-    let torque = Vec3::Y.cross(movement);
-
-    if torque.length_squared() > 0.001 {
-        for mut force in forces {
-            force.0 += torque * time.delta_secs() * MOVEMENT_ACCELERATION;
+fn move_player(
+    forces: Query<(&mut AngularVelocity, &PowerUpSlot), With<Player>>,
+    tick: Res<TickInput>,
+    time: Res<Time>,
+) {
+    let roll = tick.roll_axis();
+    if roll.length_squared() > 0.001 {
+        for (mut force, power_up) in forces {
+            force.0 +=
+                roll * time.delta_secs() * MOVEMENT_ACCELERATION * power_up.acceleration_scale();
         }
     }
 }
 
 fn on_collision_start(
     on: On<CollisionStart>,
-    filter: Query<(), With<Player>>,
+    players: Query<&GravityUp, With<Player>>,
     cmd: Commands,
     collisions: Collisions,
 ) {
-    if filter.contains(on.collider1) {
-        update_grounded(cmd, on.collider1, collisions);
+    if let Ok(up) = players.get(on.collider1) {
+        update_grounded(cmd, on.collider1, *up.0, collisions);
     }
 }
 
 fn on_collision_stop(
     on: On<CollisionEnd>,
-    filter: Query<(), With<Player>>,
+    players: Query<&GravityUp, With<Player>>,
     cmd: Commands,
     collisions: Collisions,
 ) {
-    if filter.contains(on.collider1) {
-        update_grounded(cmd, on.collider1, collisions);
+    if let Ok(up) = players.get(on.collider1) {
+        update_grounded(cmd, on.collider1, *up.0, collisions);
     }
 }
 
 // Copied from Avian3d example
-fn update_grounded(mut cmd: Commands, player: Entity, collisions: Collisions) {
+fn update_grounded(mut cmd: Commands, player: Entity, up: Vec3, collisions: Collisions) {
     let is_grounded = collisions
         .collisions_with(player)
         .filter(|pair| pair.is_touching() && pair.generates_constraints())
@@ -161,7 +227,7 @@ fn update_grounded(mut cmd: Commands, player: Entity, collisions: Collisions) {
                 }
             })
         })
-        .any(|hit| hit.angle_between(Vec3::Y).abs() <= PI / 3.0);
+        .any(|hit| hit.angle_between(up).abs() <= PI / 3.0);
 
     if is_grounded {
         cmd.entity(player).insert(Grounded);
@@ -172,61 +238,55 @@ fn update_grounded(mut cmd: Commands, player: Entity, collisions: Collisions) {
 
 // Copied from Avian3d example
 fn jump_player(
-    forces: Query<(&mut LinearVelocity, Has<Grounded>), With<Player>>,
-    mut inputs: MessageReader<PlayerInput>,
+    forces: Query<(&mut LinearVelocity, &GravityUp, &PowerUpSlot, Has<Grounded>), With<Player>>,
+    tick: Res<TickInput>,
 ) {
-    let mut input_it = inputs.read();
-    let jumping = loop {
-        let Some(input) = input_it.next() else {
-            break false;
-        };
-        if let PlayerInput::Jump = input {
-            break true;
-        }
-    };
-    inputs.clear();
-
-    if jumping {
-        for (mut vel, grounded) in forces {
+    if tick.jump {
+        for (mut vel, up, power_up, grounded) in forces {
             if grounded {
-                vel.y = JUMP_VELOCITY;
+                let up = *up.0;
+                vel.0 = vel.reject_from_normalized(up) + up * JUMP_VELOCITY * power_up.jump_scale();
             }
         }
     }
 }
 
 pub fn move_camera(
-    mut camera: Single<(&mut Transform, &PlayerCamera)>,
-    player: Single<&Transform, (With<Player>, Without<PlayerCamera>)>,
+    mut camera: Single<(&mut Transform, &PlayerCamera, &mut CameraUp)>,
+    player: Single<(&Transform, &GravityUp), (With<Player>, Without<PlayerCamera>)>,
+    time: Res<Time>,
 ) {
-    let (ref mut transform, player_camera) = *camera;
+    let (ref mut transform, player_camera, ref mut camera_up) = *camera;
+    let (player_transform, player_up) = *player;
 
-    **transform = calculate_camera_transform(player.translation, player_camera);
+    camera_up.0 = camera_up.0.slerp(
+        player_up.0,
+        (CAMERA_UP_SMOOTHING * time.delta_secs()).min(1.0),
+    );
+
+    **transform =
+        calculate_camera_transform(player_transform.translation, player_camera, camera_up.0);
 }
 
-fn calculate_camera_transform(player_pos: Vec3, player_camera: &PlayerCamera) -> Transform {
+fn calculate_camera_transform(
+    player_pos: Vec3,
+    player_camera: &PlayerCamera,
+    up: Dir3,
+) -> Transform {
     let camera_offset = Vec3::new(
         player_camera.pitch.cos() * player_camera.yaw.sin(),
         -player_camera.pitch.sin(),
         player_camera.pitch.cos() * player_camera.yaw.cos(),
     ) * player_camera.distance;
 
-    Transform::from_translation(player_pos + camera_offset).looking_at(player_pos, Vec3::Y)
+    Transform::from_translation(player_pos + up_frame(up) * camera_offset)
+        .looking_at(player_pos, up)
 }
 
-fn kill_player(
-    mut kill_msg: MessageReader<Kill>,
-    player: Query<(), With<Player>>,
-    mut cmd: Commands,
-) {
-    for kill in kill_msg.read() {
-        if player.contains(kill.to_kill) {
-            info!("Player died.");
-            cmd.trigger(PlayerDiedEvent);
-            cmd.trigger(LevelRestartEvent);
-
-            kill_msg.clear();
-            return;
-        }
+fn kill_player(killed: On<Killed>, player: Query<(), With<Player>>, mut cmd: Commands) {
+    if player.contains(killed.entity) {
+        info!("Player died.");
+        cmd.trigger(PlayerDiedEvent);
+        cmd.trigger(LevelRestartEvent);
     }
 }
@@ -1,8 +1,13 @@
 use crate::game::assets::preload::Preloads;
 use crate::game::game_state::GameState;
-use crate::game::gui::{button, menu_root, title};
+use crate::game::gui::{button, label, menu_root, title};
+use crate::game::levels::SelectedLevel;
+use crate::game::levels::index::LevelIndex;
 use crate::game::menus::main_menu::MenuState;
+use crate::game::progress::LevelProgress;
+use crate::game::records::LastRun;
 use crate::game::state::AppState;
+use crate::game::timer::format_time;
 use bevy::prelude::*;
 use bevy::ui_widgets::{Activate, observe};
 
@@ -34,18 +39,36 @@ fn disable_finish_menu(mut next_state: ResMut<NextState<FinishMenuState>>) {
     next_state.set(FinishMenuState::Disabled);
 }
 
-fn setup_finish_menu(mut cmd: Commands, fonts: Res<Preloads>) {
+fn setup_finish_menu(
+    mut cmd: Commands,
+    fonts: Res<Preloads>,
+    last_run: Res<LastRun>,
+    level: Res<SelectedLevel>,
+    index: Res<LevelIndex>,
+    progress: Res<LevelProgress>,
+) {
+    let next_button = index
+        .next_level(&level.0)
+        .filter(|next| progress.is_unlocked(&index, &next.name))
+        .map(|_| next_level_button(&fonts));
+
     cmd.spawn((
         menu_root(FinishMenuState::Main),
-        children![
-            (
+        Children::spawn((
+            Spawn((
                 title(&fonts, "Level Finished"),
                 Node {
                     bottom: px(50),
                     ..default()
+                },
+            )),
+            Spawn(run_results(&fonts, &last_run)),
+            SpawnWith(move |parent: &mut ChildSpawner| {
+                if let Some(next_button) = next_button {
+                    parent.spawn(next_button);
                 }
-            ),
-            (
+            }),
+            Spawn((
                 button(&fonts, "Exit Level", default()),
                 observe(
                     |_a: On<Activate>,
@@ -53,9 +76,71 @@ fn setup_finish_menu(mut cmd: Commands, fonts: Res<Preloads>) {
                      mut next_menu: ResMut<NextState<MenuState>>| {
                         next_state.set(AppState::MainMenu);
                         next_menu.set(MenuState::LevelSelect);
-                    }
-                )
-            )
-        ],
+                    },
+                ),
+            )),
+        )),
     ));
 }
+
+fn next_level_button(fonts: &Preloads) -> impl Bundle + use<> {
+    (
+        button(fonts, "Next Level", default()),
+        observe(
+            |_a: On<Activate>,
+             mut next_state: ResMut<NextState<AppState>>,
+             level: Res<SelectedLevel>,
+             index: Res<LevelIndex>,
+             mut cmd: Commands| {
+                if let Some(next) = index.next_level(&level.0) {
+                    next_state.set(AppState::LoadingLevel);
+                    cmd.insert_resource(SelectedLevel(next.name.clone()));
+                }
+            },
+        ),
+    )
+}
+
+fn run_results(fonts: &Preloads, last_run: &LastRun) -> impl Bundle {
+    let record = if last_run.is_record() {
+        "New record!".to_string()
+    } else {
+        format!("Best: {}", format_time(last_run.best_time()))
+    };
+
+    let par = last_run.times.par_time();
+    let gold = last_run.times.gold_time();
+
+    let mut targets = vec![];
+    if let Some(par) = par {
+        targets.push(format!("Par: {}", format_time(par)));
+    }
+    if let Some(gold) = gold {
+        targets.push(format!("Gold: {}", format_time(gold)));
+    }
+
+    let rating = if gold.is_some_and(|gold| last_run.time <= gold) {
+        "Gold time!"
+    } else if par.is_some_and(|par| last_run.time <= par) {
+        "Under par!"
+    } else {
+        ""
+    };
+
+    (
+        Node {
+            align_items: AlignItems::Center,
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            row_gap: px(10),
+            bottom: px(25),
+            ..default()
+        },
+        children![
+            label(fonts, format!("Time: {}", format_time(last_run.time))),
+            label(fonts, record),
+            label(fonts, targets.join("    ")),
+            label(fonts, rating),
+        ],
+    )
+}
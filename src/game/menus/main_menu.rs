@@ -2,8 +2,12 @@ use crate::game::gui::{ButtonSettings, button, menu_root, title};
 use crate::game::levels::SelectedLevel;
 use crate::game::levels::index::{LevelIndex, LevelIndexAsset, LevelRef};
 use crate::game::menus::options_menu::OptionsReturn;
+use crate::game::progress::LevelProgress;
+use crate::game::records::LevelRecords;
 use crate::game::state::AppState;
+use crate::game::timer::format_time;
 use bevy::prelude::*;
+use bevy::ui::InteractionDisabled;
 use bevy::ui_widgets::*;
 use crate::game::assets::preload::Preloads;
 
@@ -104,6 +108,8 @@ fn on_level_index_change(
     fonts: Res<Preloads>,
     index_handle: Res<LevelIndexAsset>,
     index: Res<Assets<LevelIndex>>,
+    records: Res<LevelRecords>,
+    progress: Res<LevelProgress>,
 ) {
     for e in msg.read() {
         if e.is_loaded_with_dependencies(&index_handle.0) {
@@ -112,7 +118,7 @@ fn on_level_index_change(
                 cmd.entity(entity).despawn();
             }
 
-            setup_level_select(cmd, fonts, index_handle, index);
+            setup_level_select(cmd, fonts, index_handle, index, records, progress);
 
             msg.clear();
             return;
@@ -125,12 +131,18 @@ fn setup_level_select(
     fonts: Res<Preloads>,
     index_handle: Res<LevelIndexAsset>,
     index: Res<Assets<LevelIndex>>,
+    records: Res<LevelRecords>,
+    progress: Res<LevelProgress>,
 ) {
     let level_buttons = index
         .get(&index_handle.0)
         .iter()
-        .flat_map(|idx| idx.order.iter().map(|name| &idx.levels[name]))
-        .map(|r| level_select_button(&fonts, r))
+        .flat_map(|idx| {
+            idx.order
+                .iter()
+                .map(|name| (&idx.levels[name], progress.is_unlocked(idx, name)))
+        })
+        .map(|(r, unlocked)| (level_select_button(&fonts, r, &records, unlocked), unlocked))
         .collect::<Vec<_>>();
 
     cmd.spawn((
@@ -156,7 +168,14 @@ fn setup_level_select(
                     column_gap: px(20),
                     ..default()
                 },
-                Children::spawn(SpawnIter(level_buttons.into_iter())),
+                Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+                    for (button, unlocked) in level_buttons {
+                        let mut button = parent.spawn(button);
+                        if !unlocked {
+                            button.insert(InteractionDisabled);
+                        }
+                    }
+                })),
             ),
             (
                 button(&fonts, "Back", default()),
@@ -170,8 +189,17 @@ fn setup_level_select(
     ));
 }
 
-fn level_select_button(fonts: &Preloads, level: &LevelRef) -> impl Bundle + use<> {
-    let display = level.display.clone();
+fn level_select_button(
+    fonts: &Preloads,
+    level: &LevelRef,
+    records: &LevelRecords,
+    unlocked: bool,
+) -> impl Bundle + use<> {
+    let display = match records.best_time(&level.name) {
+        _ if !unlocked => format!("{}\nLocked", &level.display),
+        None => level.display.clone(),
+        Some(best) => format!("{}\n{}", &level.display, format_time(best)),
+    };
     let name = level.name.clone();
 
     // we use LevelSelectButton to pass data into the observe closure because there's a bug in
@@ -2,6 +2,7 @@ use crate::game::assets::preload::Preloads;
 use crate::game::game_state::GameState;
 use crate::game::gui::{button, menu_root, title};
 use crate::game::levels::LevelRestartEvent;
+use crate::game::levels::checkpoint::ActiveCheckpoint;
 use crate::game::menus::main_menu::MenuState;
 use crate::game::menus::options_menu::OptionsReturn;
 use crate::game::state::AppState;
@@ -61,7 +62,10 @@ fn spawn_pause_menu(mut cmd: Commands, fonts: Res<Preloads>) {
                 observe(
                     |_a: On<Activate>,
                      mut cmd: Commands,
+                     mut checkpoint: ResMut<ActiveCheckpoint>,
                      mut next_state: ResMut<NextState<GameState>>| {
+                        // restarting from the menu always restarts the whole level
+                        checkpoint.0 = None;
                         cmd.trigger(LevelRestartEvent);
                         next_state.set(GameState::Playing);
                     }
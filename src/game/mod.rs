@@ -1,5 +1,7 @@
 mod assets;
 mod camera;
+#[cfg(not(target_arch = "wasm32"))]
+mod check;
 #[cfg(not(feature = "web-storage"))]
 mod dirs;
 mod game_state;
@@ -10,10 +12,17 @@ mod levels;
 mod logic;
 mod menus;
 mod music;
+mod progress;
+mod records;
+mod replay;
 mod screenshot;
 mod settings;
+#[cfg(test)]
+mod sim;
 mod startup;
 mod state;
+mod storage;
+mod timer;
 
 use crate::game::settings::GamePrefs;
 use avian3d::PhysicsPlugins;
@@ -27,72 +36,81 @@ use bevy::ui_widgets::UiWidgetsPlugins;
 use bevy::window::EnabledButtons;
 use bevy_rich_text3d::Text3dPlugin;
 use bevy_svg::SvgPlugin;
+use std::path::PathBuf;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use check::physball_check_main;
 
 pub const CANVAS_ID: &str = "game";
 
-pub fn physball_client_main() -> AppExit {
+pub fn physball_client_main(replay: Option<PathBuf>) -> AppExit {
     let prefs = GamePrefs::load();
 
-    App::new()
-        .add_plugins((
-            DefaultPlugins
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        #[cfg(feature = "window-resize")]
-                        resizable: false,
-                        #[cfg(feature = "window-resize")]
-                        resolution: (prefs.window_width, prefs.window_height).into(),
-                        enabled_buttons: EnabledButtons {
-                            minimize: true,
-                            maximize: false,
-                            close: true,
-                        },
-                        name: Some("physball".to_string()),
-                        canvas: Some(format!("#{CANVAS_ID}")),
-                        ..default()
-                    }),
-                    ..default()
-                })
-                .set(LogPlugin {
-                    filter: format!(
-                        concat!(
-                            "{default},",
-                            "symphonia_bundle_mp3::demuxer=warn,",
-                            "symphonia_format_caf::demuxer=warn,",
-                            "symphonia_format_isompf4::demuxer=warn,",
-                            "symphonia_format_mkv::demuxer=warn,",
-                            "symphonia_format_ogg::demuxer=warn,",
-                            "symphonia_format_riff::demuxer=warn,",
-                            "symphonia_format_wav::demuxer=warn,",
-                            "calloop::loop_logic=error,",
-                            "bevy_asset::server::info=error,",
-                            "avian3d::dynamics::solver::islands::sleeping=error,",
-                        ),
-                        default = bevy::log::DEFAULT_FILTER
-                    ),
-                    fmt_layer: |_| {
-                        Some(Box::new(
-                            bevy::log::tracing_subscriber::fmt::Layer::default()
-                                .without_time()
-                                .map_fmt_fields(
-                                    bevy::log::tracing_subscriber::field::MakeExt::debug_alt,
-                                )
-                                .with_writer(std::io::stderr),
-                        ))
+    let mut app = App::new();
+    app.add_plugins((
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    #[cfg(feature = "window-resize")]
+                    resizable: false,
+                    #[cfg(feature = "window-resize")]
+                    resolution: (prefs.window_width, prefs.window_height).into(),
+                    enabled_buttons: EnabledButtons {
+                        minimize: true,
+                        maximize: false,
+                        close: true,
                     },
+                    name: Some("physball".to_string()),
+                    canvas: Some(format!("#{CANVAS_ID}")),
                     ..default()
                 }),
-            PhysicsPlugins::default(),
-            SvgPlugin,
-            Text3dPlugin::default(),
-            BallphysClient,
-            UiWidgetsPlugins,
-            InputDispatchPlugin,
-            TabNavigationPlugin,
-            PhysicsDebugPlugin,
-        ))
-        .insert_resource(prefs)
-        .run()
+                ..default()
+            })
+            .set(LogPlugin {
+                filter: format!(
+                    concat!(
+                        "{default},",
+                        "symphonia_bundle_mp3::demuxer=warn,",
+                        "symphonia_format_caf::demuxer=warn,",
+                        "symphonia_format_isompf4::demuxer=warn,",
+                        "symphonia_format_mkv::demuxer=warn,",
+                        "symphonia_format_ogg::demuxer=warn,",
+                        "symphonia_format_riff::demuxer=warn,",
+                        "symphonia_format_wav::demuxer=warn,",
+                        "calloop::loop_logic=error,",
+                        "bevy_asset::server::info=error,",
+                        "avian3d::dynamics::solver::islands::sleeping=error,",
+                    ),
+                    default = bevy::log::DEFAULT_FILTER
+                ),
+                fmt_layer: |_| {
+                    Some(Box::new(
+                        bevy::log::tracing_subscriber::fmt::Layer::default()
+                            .without_time()
+                            .map_fmt_fields(
+                                bevy::log::tracing_subscriber::field::MakeExt::debug_alt,
+                            )
+                            .with_writer(std::io::stderr),
+                    ))
+                },
+                ..default()
+            }),
+        PhysicsPlugins::default(),
+        SvgPlugin,
+        Text3dPlugin::default(),
+        BallphysClient,
+        UiWidgetsPlugins,
+        InputDispatchPlugin,
+        TabNavigationPlugin,
+        PhysicsDebugPlugin,
+    ))
+    .insert_resource(prefs);
+
+    if let Some(replay) = replay.as_deref().and_then(replay::load_replay_file) {
+        app.insert_resource(replay::PendingReplay(replay));
+    }
+
+    app.run()
 }
 
 plugin_group! {
@@ -103,7 +121,6 @@ plugin_group! {
         camera:::CameraPlugin,
         input:::InputPlugin,
         gui:::GuiPlugin,
-        music:::MusicPlugin,
         menus:::MainMenuPlugin,
         menus:::OptionsMenuPlugin,
         menus:::LoadingScreenPlugin,
@@ -113,8 +130,28 @@ plugin_group! {
         levels::finish_point:::FinishPointPlugin,
         levels::button:::ButtonPlugin,
         levels::death:::DeathPlugin,
+        levels::checkpoint:::CheckpointPlugin,
+        levels::pickup:::PickupPlugin,
+        levels::gem:::GemPlugin,
+        levels::signal:::SignalPlugin,
+        levels::mover:::MoverPlugin,
+        levels::joint:::JointPlugin,
+        levels::pad:::PadPlugin,
+        levels::powerup:::PowerUpPlugin,
+        levels::gravity:::GravityPlugin,
+        levels::force:::ForceFieldPlugin,
+        levels::surface:::SurfacePlugin,
+        levels::teleporter:::TeleporterPlugin,
+        levels::crumble:::CrumblePlugin,
+        levels::spawner:::SpawnerPlugin,
+        levels::key:::KeyPlugin,
+        levels::launcher:::LauncherPlugin,
         game_state:::GameStatePlugin,
         logic:::GamePlugin,
+        timer:::TimerPlugin,
+        records:::RecordsPlugin,
+        progress:::ProgressPlugin,
+        replay:::ReplayPlugin,
         screenshot:::ScreenshotPlugin,
         gizmos:::GizmosPlugin,
     }
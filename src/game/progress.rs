@@ -0,0 +1,131 @@
+use crate::game::game_state::GameState;
+use crate::game::levels::SelectedLevel;
+use crate::game::levels::index::LevelIndex;
+use crate::game::storage;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const PROGRESS_NAME: &str = "progress";
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ProgressPlugin;
+
+impl Plugin for ProgressPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LevelProgress::load())
+            .add_systems(OnEnter(GameState::Finished), record_completion);
+    }
+}
+
+/// Which levels the player has completed and unlocked.
+#[derive(Debug, Default, Clone, PartialEq, Resource, Reflect, Serialize, Deserialize)]
+#[reflect(Debug, Default, Clone, PartialEq, Resource)]
+pub struct LevelProgress {
+    #[serde(default)]
+    pub completed: HashSet<String>,
+    #[serde(default)]
+    pub unlocked: HashSet<String>,
+}
+
+impl LevelProgress {
+    pub fn load() -> LevelProgress {
+        storage::load(PROGRESS_NAME)
+    }
+
+    pub fn save(&self) {
+        storage::save(PROGRESS_NAME, self);
+    }
+
+    pub fn is_completed(&self, level: &str) -> bool {
+        self.completed.contains(level)
+    }
+
+    /// Levels stay unlocked once unlocked, even if the index requirements change later.
+    pub fn is_unlocked(&self, index: &LevelIndex, level: &str) -> bool {
+        self.unlocked.contains(level)
+            || index
+                .requirements(level)
+                .iter()
+                .all(|required| self.is_completed(required))
+    }
+
+    /// Marks a level as completed and unlocks every level that now has its requirements met.
+    pub fn complete(&mut self, index: &LevelIndex, level: &str) {
+        self.completed.insert(level.to_string());
+
+        let unlocked = index
+            .order
+            .iter()
+            .filter(|name| self.is_unlocked(index, name))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.unlocked.extend(unlocked);
+    }
+}
+
+fn record_completion(
+    level: Res<SelectedLevel>,
+    index: Res<LevelIndex>,
+    mut progress: ResMut<LevelProgress>,
+) {
+    progress.complete(&index, &level.0);
+    progress.save();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::levels::index::LevelRef;
+    use std::collections::HashMap;
+
+    fn index(levels: Vec<(&str, Vec<&str>)>) -> LevelIndex {
+        let mut order = vec![];
+        let mut refs = HashMap::new();
+        for (name, requires) in levels {
+            order.push(name.to_string());
+            refs.insert(
+                name.to_string(),
+                LevelRef {
+                    name: name.to_string(),
+                    display: name.to_string(),
+                    path: format!("levels/{name}.level.kdl"),
+                    requires: requires.iter().map(|r| r.to_string()).collect(),
+                    times: default(),
+                },
+            );
+        }
+        LevelIndex {
+            order,
+            levels: refs,
+        }
+    }
+
+    #[test]
+    fn test_unlock_in_order() {
+        let index = index(vec![("a", vec![]), ("b", vec!["a"]), ("c", vec!["b"])]);
+        let mut progress = LevelProgress::default();
+
+        assert!(progress.is_unlocked(&index, "a"));
+        assert!(!progress.is_unlocked(&index, "b"));
+
+        progress.complete(&index, "a");
+        assert!(progress.is_unlocked(&index, "b"));
+        assert!(!progress.is_unlocked(&index, "c"));
+    }
+
+    #[test]
+    fn test_unlock_explicit_requirements() {
+        let index = index(vec![("a", vec![]), ("b", vec![]), ("c", vec!["a", "b"])]);
+        let mut progress = LevelProgress::default();
+
+        assert!(progress.is_unlocked(&index, "b"));
+
+        progress.complete(&index, "b");
+        assert!(!progress.is_unlocked(&index, "c"));
+
+        progress.complete(&index, "a");
+        assert!(progress.is_unlocked(&index, "c"));
+        assert!(progress.unlocked.contains("c"));
+    }
+}
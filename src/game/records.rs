@@ -0,0 +1,137 @@
+use crate::game::game_state::GameState;
+use crate::game::levels::index::LevelIndex;
+use crate::game::levels::serial::level::SerialLevel;
+use crate::game::levels::{LevelHandle, SelectedLevel};
+use crate::game::storage;
+use crate::game::timer::LevelTimer;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const RECORDS_NAME: &str = "records";
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct RecordsPlugin;
+
+impl Plugin for RecordsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LevelRecords::load())
+            .init_resource::<LastRun>()
+            .add_systems(OnEnter(GameState::Finished), record_run);
+    }
+}
+
+/// Best times for each level, keyed by level name.
+#[derive(Debug, Default, Clone, PartialEq, Resource, Reflect, Serialize, Deserialize)]
+#[reflect(Debug, Default, Clone, PartialEq, Resource)]
+pub struct LevelRecords {
+    #[serde(default)]
+    pub best_times: HashMap<String, Duration>,
+}
+
+impl LevelRecords {
+    pub fn load() -> LevelRecords {
+        storage::load(RECORDS_NAME)
+    }
+
+    pub fn save(&self) {
+        storage::save(RECORDS_NAME, self);
+    }
+
+    pub fn best_time(&self, level: &str) -> Option<Duration> {
+        self.best_times.get(level).copied()
+    }
+}
+
+/// Par and gold times for a level, in seconds.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Reflect, Deserialize)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub struct ParTimes {
+    #[serde(default)]
+    pub par: Option<f32>,
+    #[serde(default)]
+    pub gold: Option<f32>,
+}
+
+impl ParTimes {
+    /// Fills in any times missing from `self` with the ones from `other`.
+    pub fn or(self, other: ParTimes) -> ParTimes {
+        ParTimes {
+            par: self.par.or(other.par),
+            gold: self.gold.or(other.gold),
+        }
+    }
+
+    pub fn par_time(&self) -> Option<Duration> {
+        self.par
+            .and_then(|par| Duration::try_from_secs_f32(par).ok())
+    }
+
+    pub fn gold_time(&self) -> Option<Duration> {
+        self.gold
+            .and_then(|gold| Duration::try_from_secs_f32(gold).ok())
+    }
+
+    /// The first time that isn't a positive, finite number of seconds.
+    pub fn invalid(&self) -> Option<(&'static str, f32)> {
+        [("par", self.par), ("gold", self.gold)]
+            .into_iter()
+            .filter_map(|(name, time)| Some((name, time?)))
+            .find(|(_, time)| !(time.is_finite() && *time > 0.0))
+    }
+}
+
+/// The result of the most recently finished run.
+#[derive(Debug, Default, Clone, PartialEq, Resource, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Resource)]
+pub struct LastRun {
+    pub time: Duration,
+    pub previous_best: Option<Duration>,
+    pub times: ParTimes,
+}
+
+impl LastRun {
+    pub fn is_record(&self) -> bool {
+        self.previous_best.is_none_or(|best| self.time < best)
+    }
+
+    pub fn best_time(&self) -> Duration {
+        self.previous_best
+            .map_or(self.time, |best| best.min(self.time))
+    }
+}
+
+pub fn record_run(
+    timer: Res<LevelTimer>,
+    level: Res<SelectedLevel>,
+    index: Res<LevelIndex>,
+    level_handle: Res<LevelHandle>,
+    level_assets: Res<Assets<SerialLevel>>,
+    mut records: ResMut<LevelRecords>,
+    mut last_run: ResMut<LastRun>,
+) {
+    // times in the level file take precedence over the ones in the level index
+    let index_times = index
+        .levels
+        .get(&level.0)
+        .map(|level| level.times)
+        .unwrap_or_default();
+    let level_times = level_assets
+        .get(&level_handle.0)
+        .map(|level| level.times)
+        .unwrap_or_default();
+
+    *last_run = LastRun {
+        time: timer.elapsed,
+        previous_best: records.best_time(&level.0),
+        times: level_times.or(index_times),
+    };
+
+    info!("Finished level '{}' in {:?}", &level.0, last_run.time);
+
+    if last_run.is_record() {
+        records.best_times.insert(level.0.clone(), last_run.time);
+        records.save();
+    }
+}
@@ -0,0 +1,581 @@
+//! Recording and playback of player input.
+//!
+//! Every physics tick, the [`PlayerInput`] the player is moved with is recorded along with the
+//! camera it's relative to and the player's position. The best run of each level is shown as a translucent ghost on later attempts,
+//! and the last attempt is saved so it can be played back with `--replay <file>` to reproduce bugs.
+//!
+//! Playback sends the recorded [`PlayerInput`] at the start of every physics tick and turns the
+//! camera the way it was, so the run steps the same way no matter how many frames the ticks are
+//! spread over.
+
+use crate::game::camera::{CameraUp, PlayerCamera};
+use crate::game::game_state::GameState;
+use crate::game::input::PlayerInput;
+use crate::game::levels::checkpoint::ActiveCheckpoint;
+use crate::game::levels::serial::level::SerialLevel;
+use crate::game::levels::{LevelHandle, LevelReadyEvent, LevelRestartEvent, SelectedLevel};
+use crate::game::logic::{Player, TickInput, read_tick_input};
+use crate::game::records::{LastRun, record_run};
+use crate::game::state::AppState;
+use crate::game::storage;
+use crate::game::timer::LevelTimer;
+use bevy::prelude::*;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+const REPLAY_MAGIC: &[u8; 4] = b"PBRP";
+const REPLAY_VERSION: u8 = 3;
+
+/// The player's position is only stored every few ticks and interpolated in between.
+const POSITION_INTERVAL: usize = 4;
+
+const JUMP_FLAG: u8 = 0b0001;
+const MOVEMENT_FLAG: u8 = 0b0010;
+const ACTIVATE_FLAG: u8 = 0b0100;
+const CAMERA_UP_FLAG: u8 = 0b1000;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Recorder>()
+            .add_observer(start_run)
+            .add_observer(restart_run)
+            .add_observer(spawn_ghost)
+            .add_systems(
+                FixedPreUpdate,
+                (
+                    play_back_tick
+                        .before(read_tick_input)
+                        .run_if(resource_exists::<ReplayPlayback>),
+                    record_tick
+                        .after(read_tick_input)
+                        .run_if(not(resource_exists::<ReplayPlayback>)),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, move_ghost.run_if(in_state(AppState::Game)))
+            .add_systems(OnEnter(GameState::Finished), save_ghost.after(record_run))
+            .add_systems(OnExit(AppState::Game), (save_last_attempt, stop_playback));
+    }
+}
+
+fn tick_flags(tick: &TickInput) -> u8 {
+    let mut flags = 0;
+    if tick.jump {
+        flags |= JUMP_FLAG;
+    }
+    if tick.movement != Vec2::ZERO {
+        flags |= MOVEMENT_FLAG;
+    }
+    if tick.activate {
+        flags |= ACTIVATE_FLAG;
+    }
+    if tick.camera_up != CameraUp::default() {
+        flags |= CAMERA_UP_FLAG;
+    }
+    flags
+}
+
+/// A recorded run of a level.
+#[derive(Debug, Default, Clone, PartialEq, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub struct Replay {
+    pub level: String,
+    pub level_hash: [u8; 32],
+    pub timestep: Duration,
+    pub ticks: Vec<TickInput>,
+    /// The player's position every [`POSITION_INTERVAL`] ticks.
+    pub positions: Vec<Vec3>,
+}
+
+impl Replay {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.push(REPLAY_VERSION);
+        bytes.extend_from_slice(&(self.level.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(self.level.as_bytes());
+        bytes.extend_from_slice(&self.level_hash);
+        bytes.extend_from_slice(&(self.timestep.as_micros() as u32).to_le_bytes());
+
+        // inputs rarely change every tick, so identical ticks are stored as a single run
+        let runs = self.ticks.chunk_by(|a, b| a == b).collect::<Vec<_>>();
+        bytes.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+        for run in runs {
+            let tick = run[0];
+            bytes.extend_from_slice(&(run.len() as u32).to_le_bytes());
+            bytes.push(tick_flags(&tick));
+            write_floats(&mut bytes, &[tick.yaw]);
+            if tick.movement != Vec2::ZERO {
+                write_floats(&mut bytes, &tick.movement.to_array());
+            }
+            if tick.camera_up != CameraUp::default() {
+                write_floats(&mut bytes, &tick.camera_up.0.to_array());
+            }
+        }
+
+        bytes.extend_from_slice(&(self.positions.len() as u32).to_le_bytes());
+        for position in &self.positions {
+            write_floats(&mut bytes, &position.to_array());
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Replay, ReplayError> {
+        let mut reader = ByteReader(bytes);
+
+        if reader.take(REPLAY_MAGIC.len())? != REPLAY_MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        let version = reader.u8()?;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::Version(version));
+        }
+
+        let level_len = reader.u16()? as usize;
+        let level = String::from_utf8(reader.take(level_len)?.to_vec())?;
+        let level_hash = reader.array()?;
+        let timestep = Duration::from_micros(reader.u32()? as u64);
+        if timestep.is_zero() {
+            return Err(ReplayError::ZeroTimestep);
+        }
+
+        let mut ticks = vec![];
+        for _ in 0..reader.u32()? {
+            let len = reader.u32()? as usize;
+            let flags = reader.u8()?;
+            let yaw = reader.f32()?;
+            let tick = TickInput {
+                movement: if flags & MOVEMENT_FLAG != 0 {
+                    reader.vec2()?
+                } else {
+                    Vec2::ZERO
+                },
+                jump: flags & JUMP_FLAG != 0,
+                activate: flags & ACTIVATE_FLAG != 0,
+                yaw,
+                camera_up: if flags & CAMERA_UP_FLAG != 0 {
+                    let up = reader.vec3()?;
+                    CameraUp(Dir3::new(up).map_err(|_| ReplayError::CameraUp(up))?)
+                } else {
+                    CameraUp::default()
+                },
+            };
+            ticks.extend(std::iter::repeat_n(tick, len));
+        }
+
+        let mut positions = vec![];
+        for _ in 0..reader.u32()? {
+            positions.push(reader.vec3()?);
+        }
+
+        Ok(Replay {
+            level,
+            level_hash,
+            timestep,
+            ticks,
+            positions,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Not a replay file")]
+    BadMagic,
+    #[error("Unsupported replay version {0}")]
+    Version(u8),
+    #[error("Replay has a physics timestep of zero")]
+    ZeroTimestep,
+    #[error("Replay file is truncated")]
+    Truncated,
+    #[error("Invalid camera up direction {0}")]
+    CameraUp(Vec3),
+    #[error("Invalid level name {0}")]
+    LevelName(#[from] std::string::FromUtf8Error),
+    #[error("IO error {0}")]
+    Io(#[from] std::io::Error),
+}
+
+fn write_floats(bytes: &mut Vec<u8>, floats: &[f32]) {
+    for float in floats {
+        bytes.extend_from_slice(&float.to_le_bytes());
+    }
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ReplayError> {
+        if self.0.len() < len {
+            return Err(ReplayError::Truncated);
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ReplayError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, ReplayError> {
+        Ok(u8::from_le_bytes(self.array()?))
+    }
+
+    fn u16(&mut self) -> Result<u16, ReplayError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, ReplayError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, ReplayError> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    fn vec2(&mut self) -> Result<Vec2, ReplayError> {
+        Ok(Vec2::new(self.f32()?, self.f32()?))
+    }
+
+    fn vec3(&mut self) -> Result<Vec3, ReplayError> {
+        Ok(Vec3::new(self.f32()?, self.f32()?, self.f32()?))
+    }
+}
+
+fn replay_name(level: &str, kind: &str) -> String {
+    format!("replays/{level}-{kind}.replay")
+}
+
+/// The run currently being recorded.
+#[derive(Debug, Default, Clone, PartialEq, Resource, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Resource)]
+pub struct Recorder {
+    pub recording: bool,
+    pub replay: Replay,
+}
+
+/// A replay to play back the next time its level is started.
+#[derive(Debug, Clone, PartialEq, Resource, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Resource)]
+pub struct PendingReplay(pub Replay);
+
+/// A replay that is currently being played back.
+///
+/// Live gameplay input is ignored while this resource exists.
+#[derive(Debug, Clone, PartialEq, Resource, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub tick: usize,
+}
+
+/// A translucent ball following the best recorded run of the level.
+#[derive(Debug, Default, Clone, PartialEq, Component, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Component)]
+pub struct Ghost {
+    pub timestep: Duration,
+    pub positions: Vec<Vec3>,
+}
+
+/// Reads a replay file passed with `--replay`.
+pub fn load_replay_file(path: &Path) -> Option<Replay> {
+    let replay = std::fs::read(path)
+        .map_err(ReplayError::from)
+        .and_then(|bytes| Replay::from_bytes(&bytes));
+
+    match replay {
+        Ok(replay) => {
+            info!(
+                "Loaded replay of level '{}' from {}",
+                &replay.level,
+                path.display()
+            );
+            Some(replay)
+        }
+        Err(err) => {
+            error!("Error loading replay {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+fn start_run(
+    _on: On<LevelReadyEvent>,
+    mut cmd: Commands,
+    mut recorder: ResMut<Recorder>,
+    pending: Option<Res<PendingReplay>>,
+    level: Res<SelectedLevel>,
+    level_handle: Res<LevelHandle>,
+    level_assets: Res<Assets<SerialLevel>>,
+    time: Res<Time<Fixed>>,
+) {
+    let Some(serial) = level_assets.get(&level_handle.0) else {
+        return;
+    };
+
+    if let Some(pending) = pending.filter(|pending| pending.0.level == level.0) {
+        if pending.0.level_hash != serial.hash {
+            warn!(
+                "Replay was recorded on a different version of level '{}'",
+                &level.0
+            );
+        }
+        if pending.0.timestep != time.timestep() {
+            warn!("Replay was recorded with a different physics timestep");
+        }
+
+        info!("Playing back replay of level '{}'", &level.0);
+        cmd.insert_resource(ReplayPlayback {
+            replay: pending.0.clone(),
+            tick: 0,
+        });
+        *recorder = Recorder::default();
+        return;
+    }
+
+    *recorder = Recorder {
+        recording: true,
+        replay: Replay {
+            level: level.0.clone(),
+            level_hash: serial.hash,
+            timestep: time.timestep(),
+            ..default()
+        },
+    };
+}
+
+fn restart_run(
+    _on: On<LevelRestartEvent>,
+    mut recorder: ResMut<Recorder>,
+    playback: Option<ResMut<ReplayPlayback>>,
+    checkpoint: Res<ActiveCheckpoint>,
+) {
+    // like the timer, a recording continues when respawning at a checkpoint
+    if checkpoint.is_active() {
+        return;
+    }
+
+    recorder.replay.ticks.clear();
+    recorder.replay.positions.clear();
+
+    if let Some(mut playback) = playback {
+        playback.tick = 0;
+    }
+}
+
+fn record_tick(
+    mut recorder: ResMut<Recorder>,
+    tick: Res<TickInput>,
+    player: Query<&Transform, With<Player>>,
+) {
+    if !recorder.recording {
+        return;
+    }
+
+    let replay = &mut recorder.replay;
+    if replay.ticks.len() % POSITION_INTERVAL == 0 {
+        let position = player
+            .single()
+            .map(|player| player.translation)
+            .ok()
+            .or(replay.positions.last().copied())
+            .unwrap_or_default();
+        replay.positions.push(position);
+    }
+    replay.ticks.push(*tick);
+}
+
+fn play_back_tick(
+    mut cmd: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut inputs: MessageWriter<PlayerInput>,
+    cameras: Query<(&mut PlayerCamera, &mut CameraUp)>,
+) {
+    let Some(recorded) = playback.replay.ticks.get(playback.tick).copied() else {
+        info!("Replay finished");
+        cmd.remove_resource::<ReplayPlayback>();
+        inputs.write(PlayerInput::Movement(Vec2::ZERO));
+        return;
+    };
+
+    playback.tick += 1;
+
+    inputs.write(PlayerInput::Movement(recorded.movement));
+    if recorded.jump {
+        inputs.write(PlayerInput::Jump);
+    }
+    if recorded.activate {
+        inputs.write(PlayerInput::Activate);
+    }
+
+    // the movement is relative to the camera, so it's put back where it was when recording
+    for (mut camera, mut camera_up) in cameras {
+        camera.yaw = recorded.yaw;
+        *camera_up = recorded.camera_up;
+    }
+}
+
+fn stop_playback(mut cmd: Commands) {
+    cmd.remove_resource::<ReplayPlayback>();
+}
+
+fn save_ghost(mut recorder: ResMut<Recorder>, last_run: Res<LastRun>) {
+    if !recorder.recording {
+        return;
+    }
+    recorder.recording = false;
+
+    if last_run.is_record() {
+        storage::save_bytes(
+            &replay_name(&recorder.replay.level, "best"),
+            &recorder.replay.to_bytes(),
+        );
+    }
+}
+
+fn save_last_attempt(mut recorder: ResMut<Recorder>) {
+    if !recorder.replay.ticks.is_empty() {
+        storage::save_bytes(
+            &replay_name(&recorder.replay.level, "last"),
+            &recorder.replay.to_bytes(),
+        );
+    }
+
+    *recorder = Recorder::default();
+}
+
+fn spawn_ghost(
+    _on: On<LevelReadyEvent>,
+    mut cmd: Commands,
+    level: Res<SelectedLevel>,
+    level_handle: Res<LevelHandle>,
+    level_assets: Res<Assets<SerialLevel>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(serial) = level_assets.get(&level_handle.0) else {
+        return;
+    };
+    let Some(bytes) = storage::load_bytes(&replay_name(&level.0, "best")) else {
+        return;
+    };
+
+    let replay = match Replay::from_bytes(&bytes) {
+        Ok(replay) => replay,
+        Err(err) => {
+            warn!("Error reading ghost of level '{}': {}", &level.0, err);
+            return;
+        }
+    };
+
+    if replay.level_hash != serial.hash {
+        info!(
+            "Ghost of level '{}' is from an older version of the level",
+            &level.0
+        );
+        return;
+    }
+    let Some(&start) = replay.positions.first() else {
+        return;
+    };
+
+    cmd.spawn((
+        Ghost {
+            timestep: replay.timestep,
+            positions: replay.positions,
+        },
+        Transform::from_translation(start),
+        Mesh3d(meshes.add(Sphere::new(0.25))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(0.8, 0.9, 1.0, 0.3),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        })),
+        DespawnOnExit(AppState::Game),
+    ));
+}
+
+fn move_ghost(ghosts: Query<(&Ghost, &mut Transform)>, timer: Res<LevelTimer>) {
+    for (ghost, mut transform) in ghosts {
+        // the ghost follows the level timer, so it pauses and restarts along with the player
+        let samples =
+            timer.elapsed.as_secs_f32() / (ghost.timestep.as_secs_f32() * POSITION_INTERVAL as f32);
+        let index = samples as usize;
+        let last = ghost.positions.len() - 1;
+
+        let from = ghost.positions[index.min(last)];
+        let to = ghost.positions[(index + 1).min(last)];
+        transform.translation = from.lerp(to, samples.fract());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_round_trip() {
+        let moving = TickInput {
+            movement: Vec2::new(0.0, 1.0),
+            yaw: 0.5,
+            ..default()
+        };
+        let jumping = TickInput {
+            jump: true,
+            yaw: 0.52,
+            camera_up: CameraUp(Dir3::NEG_Z),
+            ..default()
+        };
+        let activating = TickInput {
+            activate: true,
+            ..default()
+        };
+
+        let replay = Replay {
+            level: "tutorial1".to_string(),
+            level_hash: [7; 32],
+            timestep: Duration::from_micros(15_625),
+            ticks: vec![
+                moving,
+                moving,
+                moving,
+                jumping,
+                TickInput::default(),
+                activating,
+                moving,
+            ],
+            positions: vec![Vec3::new(0.0, 0.5, 0.0), Vec3::new(0.0, 0.5, -0.1)],
+        };
+
+        assert_eq!(Replay::from_bytes(&replay.to_bytes()).unwrap(), replay);
+    }
+
+    #[test]
+    fn test_replay_rejects_bad_data() {
+        assert!(matches!(
+            Replay::from_bytes(b"nope"),
+            Err(ReplayError::BadMagic)
+        ));
+
+        assert!(matches!(
+            Replay::from_bytes(&Replay::default().to_bytes()),
+            Err(ReplayError::ZeroTimestep)
+        ));
+
+        let bytes = Replay {
+            timestep: Duration::from_micros(15_625),
+            ..default()
+        }
+        .to_bytes();
+        assert!(matches!(
+            Replay::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ReplayError::Truncated)
+        ));
+    }
+}
@@ -0,0 +1,304 @@
+//! Headless level simulation for tests.
+//!
+//! [`LevelSim`] runs a level without a window, renderer or audio. Each update advances time by
+//! exactly one physics tick, so a scripted [`PlayerInput`] sequence plays out the same way on every
+//! run, including on CI machines without a GPU.
+
+use crate::game::assets::{BuiltinAssetsPlugin, BuiltinAssetsState, load_all_builtins};
+use crate::game::camera::CameraPlugin;
+use crate::game::game_state::GameState;
+use crate::game::input::PlayerInput;
+use crate::game::levels::button::ButtonPlugin;
+use crate::game::levels::checkpoint::CheckpointPlugin;
+use crate::game::levels::crumble::CrumblePlugin;
+use crate::game::levels::death::{DeathPlugin, PlayerDiedEvent};
+use crate::game::levels::finish_point::FinishPointPlugin;
+use crate::game::levels::force::ForceFieldPlugin;
+use crate::game::levels::gem::GemPlugin;
+use crate::game::levels::gravity::GravityPlugin;
+use crate::game::levels::index::LevelIndex;
+use crate::game::levels::joint::JointPlugin;
+use crate::game::levels::key::KeyPlugin;
+use crate::game::levels::launcher::LauncherPlugin;
+use crate::game::levels::mover::MoverPlugin;
+use crate::game::levels::pad::PadPlugin;
+use crate::game::levels::pickup::PickupPlugin;
+use crate::game::levels::powerup::PowerUpPlugin;
+use crate::game::levels::signal::SignalPlugin;
+use crate::game::levels::spawner::SpawnerPlugin;
+use crate::game::levels::surface::SurfacePlugin;
+use crate::game::levels::teleporter::TeleporterPlugin;
+use crate::game::levels::{LevelsPlugin, SelectedLevel};
+use crate::game::logic::{GamePlugin, Player};
+use crate::game::state::AppState;
+use crate::game::timer::{LevelTimer, TimerPlugin};
+use avian3d::PhysicsPlugins;
+use bevy::app::PluginsState;
+use bevy::audio::{AudioLoader, AudioPlugin};
+use bevy::log::LogPlugin;
+use bevy::prelude::*;
+use bevy::render::RenderPlugin;
+use bevy::render::settings::WgpuSettings;
+use bevy::state::state::FreelyMutableState;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+use bevy_rich_text3d::Text3dPlugin;
+use std::time::{Duration, Instant};
+
+/// How long to wait for assets to load before failing.
+const LOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A sequence of inputs to send to a level, one set per physics tick.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InputScript {
+    steps: Vec<(Duration, Vec<PlayerInput>)>,
+}
+
+impl InputScript {
+    pub fn new() -> InputScript {
+        default()
+    }
+
+    /// Sends `inputs` every tick for `duration`.
+    pub fn hold(mut self, duration: Duration, inputs: impl Into<Vec<PlayerInput>>) -> InputScript {
+        self.steps.push((duration, inputs.into()));
+        self
+    }
+
+    /// Sends no inputs for `duration`.
+    pub fn wait(self, duration: Duration) -> InputScript {
+        self.hold(duration, [])
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Resource)]
+struct SimStats {
+    deaths: u32,
+}
+
+pub struct LevelSim {
+    app: App,
+}
+
+impl LevelSim {
+    /// Creates a headless app and waits for the builtin assets to load.
+    pub fn new() -> LevelSim {
+        let mut app = App::new();
+        app.add_plugins((
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    close_when_requested: false,
+                    ..default()
+                })
+                .set(RenderPlugin {
+                    render_creation: WgpuSettings {
+                        backends: None,
+                        ..default()
+                    }
+                    .into(),
+                    ..default()
+                })
+                .disable::<WinitPlugin>()
+                .disable::<AudioPlugin>()
+                .disable::<LogPlugin>(),
+            PhysicsPlugins::default(),
+            Text3dPlugin::default(),
+            BuiltinAssetsPlugin,
+            LevelsPlugin,
+            GamePlugin,
+            DeathPlugin,
+            ButtonPlugin,
+            FinishPointPlugin,
+            CheckpointPlugin,
+            TimerPlugin,
+            CameraPlugin,
+        ))
+        .add_plugins((
+            SignalPlugin,
+            MoverPlugin,
+            SurfacePlugin,
+            PadPlugin,
+            GravityPlugin,
+            ForceFieldPlugin,
+            TeleporterPlugin,
+            PickupPlugin,
+            GemPlugin,
+            PowerUpPlugin,
+            JointPlugin,
+            CrumblePlugin,
+            SpawnerPlugin,
+            KeyPlugin,
+            LauncherPlugin,
+        ))
+        .init_state::<AppState>()
+        .init_state::<GameState>()
+        // levels still reference audio, even though nothing plays it
+        .init_asset::<AudioSource>()
+        .init_asset_loader::<AudioLoader>()
+        .add_message::<PlayerInput>()
+        .init_resource::<SimStats>()
+        .add_observer(count_deaths)
+        .add_systems(Startup, load_builtins);
+
+        // one fixed timestep per update keeps physics deterministic
+        let timestep = app.world().resource::<Time<Fixed>>().timestep();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+
+        while app.plugins_state() == PluginsState::Adding {
+            bevy::tasks::tick_global_task_pools_on_main_thread();
+        }
+        app.finish();
+        app.cleanup();
+
+        let mut sim = LevelSim { app };
+        sim.update_until("builtin assets", |world| {
+            world.resource::<BuiltinAssetsState>().is_done()
+        });
+        sim
+    }
+
+    /// Loads a level and starts playing it.
+    pub fn load_level(&mut self, level: &str) {
+        self.app.insert_resource(SelectedLevel(level.to_string()));
+        self.set_state(AppState::LoadingLevel);
+        self.update_until(&format!("level '{level}'"), |world| {
+            let state = **world.resource::<State<AppState>>();
+            assert_ne!(
+                state,
+                AppState::LevelLoadingError,
+                "Error loading level '{level}'"
+            );
+            state == AppState::Game
+        });
+
+        self.set_state(GameState::Playing);
+        self.app.update();
+    }
+
+    pub fn level_index(&self) -> &LevelIndex {
+        self.app.world().resource()
+    }
+
+    /// Sends the inputs for one physics tick and runs it.
+    pub fn step(&mut self, inputs: &[PlayerInput]) {
+        self.app
+            .world_mut()
+            .write_message_batch(inputs.iter().copied());
+        self.app.update();
+    }
+
+    /// Runs `script` until it ends or the level is finished.
+    pub fn run(&mut self, script: &InputScript) {
+        let timestep = self.app.world().resource::<Time<Fixed>>().timestep();
+
+        for (duration, inputs) in script.steps.iter() {
+            let ticks = duration.as_secs_f64() / timestep.as_secs_f64();
+            for _ in 0..ticks.ceil() as u32 {
+                if self.finished() {
+                    return;
+                }
+                self.step(inputs);
+            }
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        **self.app.world().resource::<State<GameState>>() == GameState::Finished
+    }
+
+    pub fn deaths(&self) -> u32 {
+        self.app.world().resource::<SimStats>().deaths
+    }
+
+    /// Time spent in the current run of the level.
+    pub fn elapsed(&self) -> Duration {
+        self.app.world().resource::<LevelTimer>().elapsed
+    }
+
+    pub fn player_position(&mut self) -> Vec3 {
+        self.app
+            .world_mut()
+            .query_filtered::<&Transform, With<Player>>()
+            .single(self.app.world())
+            .expect("missing player")
+            .translation
+    }
+
+    fn set_state<S: FreelyMutableState>(&mut self, state: S) {
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<S>>()
+            .set(state);
+    }
+
+    fn update_until(&mut self, what: &str, mut done: impl FnMut(&World) -> bool) {
+        let start = Instant::now();
+        while !done(self.app.world()) {
+            assert!(
+                start.elapsed() < LOAD_TIMEOUT,
+                "Timed out waiting for {what}"
+            );
+            self.app.update();
+            // give the asset loaders a chance to run
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+fn load_builtins(mut cmd: Commands, asset_server: Res<AssetServer>) {
+    load_all_builtins(&mut cmd, &asset_server);
+}
+
+fn count_deaths(_on: On<PlayerDiedEvent>, mut stats: ResMut<SimStats>) {
+    stats.deaths += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORWARD: PlayerInput = PlayerInput::Movement(Vec2::Y);
+    const BACKWARD: PlayerInput = PlayerInput::Movement(Vec2::NEG_Y);
+
+    #[test]
+    fn test_all_levels_idle() {
+        let levels = LevelSim::new().level_index().order.clone();
+
+        for level in levels {
+            let mut sim = LevelSim::new();
+            sim.load_level(&level);
+            sim.run(&InputScript::new().wait(Duration::from_secs(3)));
+
+            assert_eq!(sim.deaths(), 0, "player died idling in level '{level}'");
+            assert!(!sim.finished(), "level '{level}' finished by itself");
+        }
+    }
+
+    #[test]
+    fn test_tutorial1_finish() {
+        let mut sim = LevelSim::new();
+        sim.load_level("tutorial1");
+        sim.run(&InputScript::new().hold(Duration::from_secs(20), [FORWARD]));
+
+        assert!(
+            sim.finished(),
+            "player stopped at {}",
+            sim.player_position()
+        );
+        assert_eq!(sim.deaths(), 0);
+        assert!(sim.elapsed() < Duration::from_secs(20));
+    }
+
+    #[test]
+    fn test_tutorial1_fall_off() {
+        let mut sim = LevelSim::new();
+        sim.load_level("tutorial1");
+        sim.run(&InputScript::new().hold(Duration::from_secs(10), [BACKWARD]));
+
+        assert!(!sim.finished());
+        assert!(sim.deaths() >= 1);
+    }
+}
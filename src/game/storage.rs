@@ -0,0 +1,227 @@
+//! Persistent storage for JSON values and raw bytes.
+//!
+//! On desktop, each stored value is a file in one of the project's directories. With the
+//! `web-storage` feature, values are stored in the browser's local storage instead.
+
+use bevy::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+#[cfg(not(feature = "web-storage"))]
+use crate::game::dirs::PROJECT_DIRS;
+#[cfg(not(feature = "web-storage"))]
+use std::fs::{OpenOptions, create_dir_all};
+
+#[cfg(feature = "web-storage")]
+const KEY_PREFIX: &str = "com.kneelawk.physball/";
+
+/// Loads the value stored under `name`, falling back to the default if it is missing or broken.
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    #[cfg(not(feature = "web-storage"))]
+    {
+        let path = PROJECT_DIRS.preference_dir().join(format!("{name}.json"));
+
+        info!("Loading {} from {:?}", name, &path);
+
+        if !path.exists() {
+            info!("{} file does not exist. Using defaults...", name);
+            return default();
+        }
+
+        let file = match OpenOptions::new().read(true).open(path) {
+            Ok(f) => f,
+            Err(err) => {
+                warn!("Error opening {} file {:?}", name, err);
+                return default();
+            }
+        };
+
+        serde_json::from_reader(file).unwrap_or_else(|err| {
+            warn!("Error parsing {} file {:?}", name, err);
+            default()
+        })
+    }
+    #[cfg(feature = "web-storage")]
+    {
+        use crate::or_return;
+
+        let window = or_return!(_r => {
+            warn!("Unable to get window");
+            return default();
+        } : Option(web_sys::window()));
+        let storage = or_return!(ret_input => {
+            warn!("Unable to get storage: {ret_input:?}");
+            return default()
+        } : Option(Result(window.local_storage())));
+        let stored = or_return!(_r => {
+            return default();
+        } : Option(Result(storage.get_item(&format!("{KEY_PREFIX}{name}")))));
+
+        info!("Loading {} from storage", name);
+
+        serde_json::from_str(&stored).unwrap_or_else(|err| {
+            warn!("Error parsing {} {:?}", name, err);
+            default()
+        })
+    }
+}
+
+/// Stores `value` under `name`, logging any errors.
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    #[cfg(not(feature = "web-storage"))]
+    {
+        let path = PROJECT_DIRS.preference_dir().join(format!("{name}.json"));
+
+        info!("Writing {} to {:?}", name, &path);
+
+        if !path.parent().unwrap().exists() {
+            match create_dir_all(path.parent().unwrap()) {
+                Ok(_) => {}
+                Err(err) => {
+                    error!("Error creating {} dir {:?}", name, err);
+                    return;
+                }
+            }
+        }
+
+        let file = match OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(path)
+        {
+            Ok(f) => f,
+            Err(err) => {
+                error!("Error opening {} file for writing {:?}", name, err);
+                return;
+            }
+        };
+
+        match serde_json::to_writer_pretty(file, value) {
+            Ok(_) => {}
+            Err(err) => {
+                error!("Error writing {} file {:?}", name, err);
+            }
+        }
+    }
+    #[cfg(feature = "web-storage")]
+    {
+        use crate::or_return;
+
+        let window = or_return!(_r => {
+            error!("Unable to get window");
+            return;
+        } : Option(web_sys::window()));
+        let storage = or_return!(ret_input => {
+            error!("Unable to get storage: {ret_input:?}");
+            return;
+        } : Option(Result(window.local_storage())));
+
+        let string = or_return!(ret_input => {
+            error!("Error serializing {}: {ret_input:?}", name);
+            return;
+        } : Result(serde_json::to_string(value)));
+
+        or_return!(ret_input => {
+            error!("Error storing {}: {ret_input:?}", name)
+        } : Result(storage.set_item(&format!("{KEY_PREFIX}{name}"), &string)));
+    }
+}
+
+/// Loads the raw bytes stored under `name`, if there are any.
+///
+/// Unlike JSON values, byte blobs live in the project's data directory on desktop.
+pub fn load_bytes(name: &str) -> Option<Vec<u8>> {
+    #[cfg(not(feature = "web-storage"))]
+    {
+        let path = PROJECT_DIRS.data_dir().join(name);
+
+        if !path.exists() {
+            return None;
+        }
+
+        info!("Loading {} from {:?}", name, &path);
+
+        std::fs::read(&path)
+            .inspect_err(|err| warn!("Error reading {} file {:?}", name, err))
+            .ok()
+    }
+    #[cfg(feature = "web-storage")]
+    {
+        use crate::or_return;
+
+        let window = or_return!(_r => {
+            warn!("Unable to get window");
+            return None;
+        } : Option(web_sys::window()));
+        let storage = or_return!(ret_input => {
+            warn!("Unable to get storage: {ret_input:?}");
+            return None;
+        } : Option(Result(window.local_storage())));
+        let stored = or_return!(_r => {
+            return None;
+        } : Option(Result(storage.get_item(&format!("{KEY_PREFIX}{name}")))));
+
+        info!("Loading {} from storage", name);
+
+        // local storage only holds strings, so bytes are stored as hex
+        let bytes = (0..stored.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(stored.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<_>>>();
+        if bytes.is_none() {
+            warn!("Error parsing {}", name);
+        }
+        bytes
+    }
+}
+
+/// Stores raw bytes under `name`, logging any errors.
+pub fn save_bytes(name: &str, bytes: &[u8]) {
+    #[cfg(not(feature = "web-storage"))]
+    {
+        let path = PROJECT_DIRS.data_dir().join(name);
+
+        info!("Writing {} to {:?}", name, &path);
+
+        if !path.parent().unwrap().exists() {
+            match create_dir_all(path.parent().unwrap()) {
+                Ok(_) => {}
+                Err(err) => {
+                    error!("Error creating {} dir {:?}", name, err);
+                    return;
+                }
+            }
+        }
+
+        match std::fs::write(&path, bytes) {
+            Ok(_) => {}
+            Err(err) => {
+                error!("Error writing {} file {:?}", name, err);
+            }
+        }
+    }
+    #[cfg(feature = "web-storage")]
+    {
+        use crate::or_return;
+        use std::fmt::Write;
+
+        let window = or_return!(_r => {
+            error!("Unable to get window");
+            return;
+        } : Option(web_sys::window()));
+        let storage = or_return!(ret_input => {
+            error!("Unable to get storage: {ret_input:?}");
+            return;
+        } : Option(Result(window.local_storage())));
+
+        let mut string = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            let _ = write!(string, "{byte:02x}");
+        }
+
+        or_return!(ret_input => {
+            error!("Error storing {}: {ret_input:?}", name)
+        } : Result(storage.set_item(&format!("{KEY_PREFIX}{name}"), &string)));
+    }
+}
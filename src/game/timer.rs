@@ -0,0 +1,88 @@
+use crate::game::game_state::GameState;
+use crate::game::levels::checkpoint::ActiveCheckpoint;
+use crate::game::levels::{LevelReadyEvent, LevelRestartEvent};
+use crate::game::state::AppState;
+use bevy::prelude::*;
+use std::time::Duration;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct TimerPlugin;
+
+impl Plugin for TimerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelTimer>()
+            .add_observer(start_timer)
+            .add_observer(restart_timer)
+            .add_systems(Update, tick_timer.run_if(in_state(GameState::Playing)))
+            .add_systems(OnExit(AppState::Game), stop_timer);
+    }
+}
+
+/// Time spent in the current run of a level.
+///
+/// This only ticks while the game is [`GameState::Playing`], so pausing also pauses the timer.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Resource, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash, Resource)]
+pub struct LevelTimer {
+    pub elapsed: Duration,
+    pub running: bool,
+}
+
+impl LevelTimer {
+    pub fn start(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.running = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+}
+
+/// Formats a run time as `minutes:seconds.hundredths`.
+pub fn format_time(time: Duration) -> String {
+    let centis = time.as_millis() / 10;
+    format!(
+        "{}:{:02}.{:02}",
+        centis / 6000,
+        centis / 100 % 60,
+        centis % 100
+    )
+}
+
+fn start_timer(_on: On<LevelReadyEvent>, mut timer: ResMut<LevelTimer>) {
+    timer.start();
+}
+
+fn restart_timer(
+    _on: On<LevelRestartEvent>,
+    mut timer: ResMut<LevelTimer>,
+    checkpoint: Res<ActiveCheckpoint>,
+) {
+    // the run continues when respawning at a checkpoint
+    if !checkpoint.is_active() {
+        timer.start();
+    }
+}
+
+fn tick_timer(mut timer: ResMut<LevelTimer>, time: Res<Time>) {
+    if timer.running {
+        timer.elapsed += time.delta();
+    }
+}
+
+fn stop_timer(mut timer: ResMut<LevelTimer>) {
+    timer.stop();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(Duration::from_millis(0)), "0:00.00");
+        assert_eq!(format_time(Duration::from_millis(12_345)), "0:12.34");
+        assert_eq!(format_time(Duration::from_millis(754_990)), "12:34.99");
+    }
+}
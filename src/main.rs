@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::process::{ExitCode, Termination};
 
 mod game;
@@ -10,5 +11,18 @@ fn main() -> ExitCode {
     }
 
     // maybe use clap later
-    game::physball_client_main().report()
+    #[cfg(not(target_arch = "wasm32"))]
+    if std::env::args().any(|arg| arg == "--check") {
+        return game::physball_check_main().report();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let replay = std::env::args()
+        .skip_while(|arg| arg != "--replay")
+        .nth(1)
+        .map(PathBuf::from);
+    #[cfg(target_arch = "wasm32")]
+    let replay = None;
+
+    game::physball_client_main(replay).report()
 }